@@ -0,0 +1,128 @@
+use std::error::Error;
+use std::fmt;
+
+/// Behavior of the [Counter](crate::Counter) operators `+=`, `-=`, `+` and `-`
+/// when the result does not fit into the value type.
+///
+/// The policy is shared by all clones of a [Counter](crate::Counter).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OverflowPolicy {
+    /// Wrap around the boundary of the value type.
+    #[default]
+    Wrap,
+    /// Clamp the value at the boundary of the value type.
+    Saturate,
+    /// Panic leaving the value unchanged.
+    Panic,
+}
+
+impl OverflowPolicy {
+    pub(crate) fn into_u8(self) -> u8 {
+        match self {
+            Self::Wrap => 0,
+            Self::Saturate => 1,
+            Self::Panic => 2,
+        }
+    }
+
+    pub(crate) fn from_u8(policy: u8) -> Self {
+        match policy {
+            0 => Self::Wrap,
+            1 => Self::Saturate,
+            2 => Self::Panic,
+            _ => unreachable!("Overflow policy is always stored from a valid variant"),
+        }
+    }
+}
+
+/// Error returned by the checked arithmetic of a [Counter](crate::Counter).
+///
+/// The value of the counter is left unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArithmeticError {
+    /// The result exceeds the maximum of the value type.
+    Overflow,
+    /// The result is below the minimum of the value type.
+    Underflow,
+}
+
+impl fmt::Display for ArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "Counter value overflow"),
+            Self::Underflow => write!(f, "Counter value underflow"),
+        }
+    }
+}
+
+impl Error for ArithmeticError {}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::{ArithmeticError, Counter, OverflowPolicy};
+    use std::ops::Mul;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[test]
+    fn checked_arithmetic_keeps_value() {
+        let counter = Counter::new(3, 10);
+
+        assert_eq!(counter.try_sub(6), Err(ArithmeticError::Underflow));
+        assert_eq!(counter.value(), 3);
+
+        assert_eq!(counter.try_add(usize::MAX), Err(ArithmeticError::Overflow));
+        assert_eq!(counter.value(), 3);
+
+        assert_eq!(counter.try_sub(2), Ok(1));
+        assert_eq!(counter.saturating_sub(6), 0);
+        assert_eq!(counter.saturating_add(usize::MAX), usize::MAX);
+    }
+
+    #[test]
+    fn saturating_policy_clamps_operators() {
+        let mut counter = Counter::new(3, 10).with_policy(OverflowPolicy::Saturate);
+        assert_eq!(counter.policy(), OverflowPolicy::Saturate);
+
+        counter -= 6;
+        assert_eq!(counter.value(), 0);
+
+        counter = counter + usize::MAX + 1;
+        assert_eq!(counter.value(), usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "Counter value underflow")]
+    fn panic_policy_panics_on_underflow() {
+        let mut counter = Counter::new(3, 10).with_policy(OverflowPolicy::Panic);
+        counter -= 6;
+    }
+
+    #[tokio::test]
+    async fn read_modify_write_wakes_waiters() {
+        let counting_interval = Duration::from_millis(10);
+
+        let tickets = Counter::to(3);
+        assert_eq!(tickets.fetch_add(1), 0);
+        assert_eq!(tickets.fetch_add(1), 1);
+        assert_eq!(tickets.fetch_sub(1), 2);
+
+        // Every read-modify-write operation wakes the waiters.
+        let counter = tickets.clone();
+        tokio::spawn(async move {
+            time::sleep(counting_interval).await;
+            assert_eq!(counter.compare_exchange(2, 5), Err(1));
+            assert_eq!(counter.compare_exchange(1, 2), Ok(1));
+            time::sleep(counting_interval).await;
+            assert_eq!(counter.fetch_update(|v| v.checked_mul(2)), Ok(2));
+        });
+
+        let r = time::timeout(counting_interval.mul(20), tickets.wait_for(2)).await;
+        assert!(matches!(r, Ok(Ok(2))));
+
+        let r = time::timeout(counting_interval.mul(20), tickets.wait()).await;
+        assert!(matches!(r, Ok(Ok(4))));
+        assert_eq!(tickets.swap(0), 4);
+        assert_eq!(tickets.value(), 0);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::Closed;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Instant;
+
+/// Error returned by [Counter::wait_blocking_timeout](crate::Counter::wait_blocking_timeout).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WaitTimeoutError<T> {
+    /// The target is not reached in time, holding the value observed last.
+    Timeout { value: T },
+    /// The counter is closed before the target is reached.
+    Closed(Closed<T>),
+}
+
+impl<T: fmt::Display> fmt::Display for WaitTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout { value } => write!(f, "Counter wait timed out at value {value}"),
+            Self::Closed(closed) => closed.fmt(f),
+        }
+    }
+}
+
+impl<T: fmt::Debug + fmt::Display> Error for WaitTimeoutError<T> {}
+
+impl<T> From<Closed<T>> for WaitTimeoutError<T> {
+    fn from(closed: Closed<T>) -> Self {
+        Self::Closed(closed)
+    }
+}
+
+/// Waker unparking the thread blocked on a future.
+///
+/// It is registered in the waiters like any other waker,
+/// so blocked threads are woken by the same modifications as pending tasks.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Poll `future` on the current thread parking it in between,
+/// returns `None` if `future` is still pending at `deadline`.
+pub(crate) fn block_on<F: Future>(future: F, deadline: Option<Instant>) -> Option<F::Output> {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return Some(output);
+        }
+
+        // Spurious unparks only cause another poll.
+        match deadline {
+            None => thread::park(),
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return None;
+                }
+                thread::park_timeout(deadline - now);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::{Closed, Counter, WaitTimeoutError};
+    use std::ops::Mul;
+    use std::thread;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn sync_and_async_waiters_share_counter() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::to(10);
+        let mut count = counter.clone();
+
+        let blocked = thread::spawn({
+            let counter = counter.clone();
+            move || counter.wait_blocking(10)
+        });
+        let timed = thread::spawn({
+            let counter = counter.clone();
+            move || counter.wait_blocking_timeout(20, counting_interval.mul(5))
+        });
+
+        // Update the counter from a plain thread.
+        let writer = thread::spawn(move || {
+            for _ in 0u8..2 {
+                thread::sleep(counting_interval);
+                count += 5;
+            }
+        });
+
+        let r = time::timeout(counting_interval.mul(20), counter.wait_for(10)).await;
+        assert!(matches!(r, Ok(Ok(10))));
+
+        let r = blocked.join().expect("Blocked thread must not panic");
+        assert_eq!(r, Ok(10));
+
+        let r = timed.join().expect("Blocked thread must not panic");
+        // The value observed at the deadline depends on how far the writer has got.
+        assert!(matches!(r, Err(WaitTimeoutError::Timeout { .. })));
+
+        writer.join().expect("Writer thread must not panic");
+
+        let reader = counter.reader();
+        let r = reader.wait_blocking_timeout(10, counting_interval);
+        assert_eq!(r, Ok(10));
+
+        counter.close();
+        assert_eq!(counter.wait_blocking(20), Err(Closed { value: 10 }));
+        assert_eq!(reader.wait_blocking(20), Err(Closed { value: 10 }));
+    }
+}
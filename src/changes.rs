@@ -0,0 +1,115 @@
+use crate::wait::Waiter;
+use crate::{Inner, Value};
+use futures_core::{FusedStream, Stream};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Stream of the values of a [Counter](crate::Counter), starting with the current one.
+///
+/// Modifications made while the stream is not polled are coalesced,
+/// the stream never misses the latest value.
+/// The stream ends once the [Counter](crate::Counter) is closed and its latest value is yielded.
+/// Created by [Counter::changes](crate::Counter::changes).
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Changes<T: Value = usize> {
+    waiter: Waiter<T, Arc<Inner<T>>>,
+    /// Version of the last yielded value.
+    seen: Option<u64>,
+    closed: bool,
+}
+
+impl<T: Value> Changes<T> {
+    pub(crate) fn new(inner: Arc<Inner<T>>) -> Self {
+        Self {
+            waiter: Waiter::new(inner),
+            seen: None,
+            closed: false,
+        }
+    }
+}
+
+impl<T: Value> Stream for Changes<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.closed {
+            return Poll::Ready(None);
+        }
+
+        this.waiter.poll_changed(cx, this.seen).map(|r| match r {
+            Ok((version, value)) => {
+                this.seen = Some(version);
+                Some(value)
+            }
+            Err(_) => {
+                this.closed = true;
+                None
+            }
+        })
+    }
+}
+
+impl<T: Value> FusedStream for Changes<T> {
+    fn is_terminated(&self) -> bool {
+        self.closed
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::Counter;
+    use futures::future::FusedFuture;
+    use futures::stream::FusedStream;
+    use futures::StreamExt;
+    use std::ops::Mul;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn changes_coalesce_to_latest() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::to(10);
+        let mut count = counter.clone();
+        let mut changes = counter.changes();
+
+        assert_eq!(changes.next().await, Some(0));
+
+        // Several modifications before the stream is polled yield the latest value once.
+        count += 1;
+        count += 2;
+        count -= 1;
+        assert_eq!(changes.next().await, Some(2));
+
+        let r = time::timeout(counting_interval, changes.next()).await;
+        assert!(r.is_err());
+
+        tokio::spawn(async move {
+            time::sleep(counting_interval).await;
+            count.set(7);
+        });
+
+        let r = time::timeout(counting_interval.mul(20), changes.next()).await;
+        assert!(matches!(r, Ok(Some(7))));
+
+        // The stream ends once the counter is closed.
+        counter.close();
+        assert_eq!(changes.next().await, None);
+        assert!(changes.is_terminated());
+    }
+
+    #[tokio::test]
+    async fn wait_is_fused() {
+        let counter = Counter::new(3, 3);
+        let mut wait = counter.wait();
+
+        assert!(!wait.is_terminated());
+        assert_eq!((&mut wait).await, Ok(3));
+        assert!(wait.is_terminated());
+    }
+}
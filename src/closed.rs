@@ -0,0 +1,79 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error returned by the futures of a [Counter](crate::Counter) once it is closed
+/// before they are satisfied.
+///
+/// A counter is closed explicitly by [Counter::close](crate::Counter::close)
+/// or implicitly once its last handle is dropped, since nothing can modify the value anymore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Closed<T> {
+    /// Value of the counter observed by the failed future.
+    pub value: T,
+}
+
+impl<T: fmt::Display> fmt::Display for Closed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Counter closed at value {}", self.value)
+    }
+}
+
+impl<T: fmt::Debug + fmt::Display> Error for Closed<T> {}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::{Closed, Counter};
+    use std::ops::Mul;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn close_fails_pending_waiters() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::to(10);
+        let mut count = counter.clone();
+        let waiter = tokio::spawn(time::timeout(
+            counting_interval.mul(20),
+            counter.wait_for_owned(10),
+        ));
+
+        // The producer gives up halfway.
+        tokio::spawn(async move {
+            time::sleep(counting_interval).await;
+            count += 5;
+            count.close();
+        });
+
+        let r = waiter.await.expect("Waiting task must not panic");
+        assert!(matches!(r, Ok(Err(Closed { value: 5 }))));
+        assert!(counter.is_closed());
+
+        // Satisfied futures still resolve after the counter is closed.
+        let r = time::timeout(counting_interval, counter.wait_for(5)).await;
+        assert!(matches!(r, Ok(Ok(5))));
+    }
+
+    #[tokio::test]
+    async fn dropping_last_handle_closes() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::to(10);
+        let mut count = counter.clone();
+        let wait = counter.wait_for_owned(10);
+        drop(counter);
+
+        // The producer finishes early dropping the last handle.
+        tokio::spawn(async move {
+            time::sleep(counting_interval).await;
+            count += 3;
+        });
+
+        let r = time::timeout(counting_interval.mul(20), wait).await;
+        assert!(matches!(r, Ok(Err(Closed { value: 3 }))));
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{Closed, Counter, Value, WaitFor};
+#[cfg(feature = "futures")]
+use futures_core::FusedFuture;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Create a future resolving once every [Counter] of `counters` reaches its `target`.
+///
+/// The future resolves with the outcomes of the counters in the order of `counters`,
+/// a closed counter fails with [Closed] without affecting the others.
+pub fn wait_all<'a, T, I>(counters: I) -> WaitAll<'a, T>
+where
+    T: Value,
+    I: IntoIterator<Item = &'a Counter<T>>,
+{
+    let waits = counters
+        .into_iter()
+        .map(|counter| Slot::Pending(counter.wait()))
+        .collect();
+    WaitAll { waits, done: false }
+}
+
+/// Create a future resolving once any [Counter] of `counters` reaches its `target`.
+///
+/// The future resolves with the index of the first resolved counter in `counters` and its outcome.
+///
+/// # Panics
+///
+/// Panics if `counters` is empty, since such a future could never resolve.
+pub fn wait_any<'a, T, I>(counters: I) -> WaitAny<'a, T>
+where
+    T: Value,
+    I: IntoIterator<Item = &'a Counter<T>>,
+{
+    let waits = counters.into_iter().map(Counter::wait).collect::<Vec<_>>();
+    assert!(!waits.is_empty(), "Waiting for any of no counters");
+    WaitAny { waits, done: false }
+}
+
+/// Wait for a single counter within [WaitAll].
+#[derive(Debug)]
+enum Slot<'a, T: Value> {
+    Pending(WaitFor<'a, T>),
+    Ready(Result<T, Closed<T>>),
+    Taken,
+}
+
+/// Future resolving once every [Counter] of a collection reaches its `target`.
+///
+/// Created by [wait_all].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitAll<'a, T: Value = usize> {
+    waits: Vec<Slot<'a, T>>,
+    done: bool,
+}
+
+impl<T: Value> Future for WaitAll<'_, T> {
+    type Output = Vec<Result<T, Closed<T>>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        let mut pending = false;
+        for slot in &mut this.waits {
+            if let Slot::Pending(wait) = slot {
+                match Pin::new(wait).poll(cx) {
+                    Poll::Ready(r) => *slot = Slot::Ready(r),
+                    Poll::Pending => pending = true,
+                }
+            }
+        }
+        if pending {
+            return Poll::Pending;
+        }
+
+        this.done = true;
+        let outcomes = this
+            .waits
+            .iter_mut()
+            .map(|slot| match mem::replace(slot, Slot::Taken) {
+                Slot::Ready(r) => r,
+                _ => panic!("WaitAll polled after completion"),
+            })
+            .collect();
+        Poll::Ready(outcomes)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Value> FusedFuture for WaitAll<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+/// Future resolving once any [Counter] of a collection reaches its `target`.
+///
+/// Created by [wait_any].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitAny<'a, T: Value = usize> {
+    waits: Vec<WaitFor<'a, T>>,
+    done: bool,
+}
+
+impl<T: Value> Future for WaitAny<'_, T> {
+    type Output = (usize, Result<T, Closed<T>>);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        for (i, wait) in this.waits.iter_mut().enumerate() {
+            if let Poll::Ready(r) = Pin::new(wait).poll(cx) {
+                this.done = true;
+                return Poll::Ready((i, r));
+            }
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Value> FusedFuture for WaitAny<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::{wait_all, wait_any, Closed, Counter};
+    use std::ops::Mul;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn wait_for_all_and_any_worker() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let workers = [Counter::to(3), Counter::to(2), Counter::to(5)];
+        let counts = workers.iter().map(Counter::clone).collect::<Vec<_>>();
+
+        // Every worker counts at the same pace, the second one is done first.
+        tokio::spawn(async move {
+            for _ in 0u8..5 {
+                time::sleep(counting_interval).await;
+                for mut count in counts.iter().cloned() {
+                    count += 1;
+                }
+            }
+        });
+
+        let r = time::timeout(counting_interval.mul(20), wait_any(&workers)).await;
+        assert!(matches!(r, Ok((1, Ok(2)))));
+
+        let r = time::timeout(counting_interval.mul(20), wait_all(&workers)).await;
+        assert_eq!(r.ok(), Some(vec![Ok(3), Ok(2), Ok(5)]));
+
+        workers[0].set_target(10);
+        workers[0].close();
+        let r = time::timeout(counting_interval, wait_all(&workers)).await;
+        assert_eq!(r.ok(), Some(vec![Err(Closed { value: 5 }), Ok(5), Ok(5)]));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::wait::Waiter;
+use crate::{Closed, Inner, Value};
+#[cfg(feature = "futures")]
+use futures_core::FusedFuture;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Threshold watched by a single [WaitEdge], updated under the lock of the waiters.
+#[derive(Debug)]
+pub(crate) struct Edge<T> {
+    threshold: T,
+    rising: bool,
+    /// Value which crossed the threshold first.
+    crossed: Option<T>,
+}
+
+impl<T: Value> Edge<T> {
+    pub(crate) fn new(threshold: T, rising: bool) -> Self {
+        Self {
+            threshold,
+            rising,
+            crossed: None,
+        }
+    }
+
+    /// Record whether the modification from `prev` to `new` crosses the threshold.
+    ///
+    /// Every modification knows its own previous value, so crossings are detected
+    /// exactly even if concurrent modifications are recorded out of order.
+    pub(crate) fn observe(&mut self, prev: T, new: T) {
+        let crossing = if self.rising {
+            prev < self.threshold && self.threshold <= new
+        } else {
+            self.threshold <= prev && new < self.threshold
+        };
+        if crossing && self.crossed.is_none() {
+            self.crossed = Some(new);
+        }
+    }
+
+    pub(crate) fn crossed(&self) -> Option<T> {
+        self.crossed
+    }
+}
+
+/// Future resolving once a modification of a borrowed [Counter](crate::Counter)
+/// crosses a threshold after the future is created.
+///
+/// Unlike the other futures, the current value alone never resolves it.
+/// Created by [Counter::wait_rising](crate::Counter::wait_rising)
+/// and [Counter::wait_falling](crate::Counter::wait_falling).
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitEdge<'a, T: Value = usize> {
+    waiter: Waiter<T, &'a Inner<T>>,
+    edge: usize,
+}
+
+impl<'a, T: Value> WaitEdge<'a, T> {
+    /// The edge is watched from creation, so crossings before the first poll are not missed.
+    pub(crate) fn new(inner: &'a Inner<T>, threshold: T, rising: bool) -> Self {
+        Self {
+            edge: inner.add_edge(Edge::new(threshold, rising)),
+            waiter: Waiter::new(inner),
+        }
+    }
+}
+
+impl<T: Value> Future for WaitEdge<'_, T> {
+    type Output = Result<T, Closed<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.waiter.poll_edge(cx, this.edge)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Value> FusedFuture for WaitEdge<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.waiter.is_done()
+    }
+}
+
+impl<T: Value> Drop for WaitEdge<'_, T> {
+    fn drop(&mut self) {
+        self.waiter.remove_edge(self.edge);
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::Counter;
+    use std::ops::Mul;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn edges_fire_on_crossing_only() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let load = Counter::new(90, 100);
+        let mut count = load.clone();
+
+        // Already high, no stale alert.
+        let r = time::timeout(counting_interval, load.wait_rising(80)).await;
+        assert!(r.is_err());
+
+        let rising = load.wait_rising(80);
+        let falling = load.wait_falling(50);
+
+        // Crossings happen before the futures are polled.
+        count -= 60;
+        count += 50;
+
+        let r = time::timeout(counting_interval.mul(20), rising).await;
+        assert!(matches!(r, Ok(Ok(80))));
+        let r = time::timeout(counting_interval.mul(20), falling).await;
+        assert!(matches!(r, Ok(Ok(30))));
+    }
+}
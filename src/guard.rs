@@ -0,0 +1,75 @@
+use crate::{Counter, Value};
+
+/// Guard accounting for work in flight on a [Counter].
+///
+/// The value is incremented by `n` when the guard is created and decremented by `n`
+/// when it is dropped, including during unwinding, so the count never leaks on early returns.
+/// The guard owns a handle to the [Counter], thus it keeps the counter open and is `'static`.
+/// Created by [Counter::guard] and [Counter::guard_n].
+#[derive(Debug)]
+#[must_use = "dropping the guard immediately decrements the counter"]
+pub struct CounterGuard<T: Value = usize> {
+    counter: Counter<T>,
+    n: T,
+}
+
+impl<T: Value> CounterGuard<T> {
+    pub(crate) fn new(counter: Counter<T>, n: T) -> Self {
+        counter.inner.inc(n);
+        Self { counter, n }
+    }
+
+    /// Fetch the amount the guard accounts for.
+    pub fn n(&self) -> T {
+        self.n
+    }
+}
+
+impl<T: Value> Drop for CounterGuard<T> {
+    fn drop(&mut self) {
+        self.counter.inner.dec(self.n);
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::Counter;
+    use std::ops::Mul;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn guards_release_on_drop_and_panic() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::to(10);
+
+        let guards = (0..3).map(|_| counter.guard()).collect::<Vec<_>>();
+        let batch = counter.guard_n(4);
+        assert_eq!(counter.value(), 7);
+
+        // A task panicking while holding a guard still releases it.
+        let crashed = tokio::spawn({
+            let guard = counter.guard();
+            async move {
+                time::sleep(counting_interval).await;
+                let _guard = guard;
+                panic!("Task crashed");
+            }
+        });
+
+        drop(batch);
+        tokio::spawn(async move {
+            for guard in guards {
+                time::sleep(counting_interval).await;
+                drop(guard);
+            }
+        });
+
+        let r = time::timeout(counting_interval.mul(20), counter.wait_zero()).await;
+        assert!(matches!(r, Ok(Ok(0))));
+        assert!(crashed.await.is_err());
+    }
+}
@@ -1,126 +1,961 @@
-use std::future::Future;
+use std::future::IntoFuture;
 use std::ops::{Add, AddAssign, Sub, SubAssign};
-use std::pin::Pin;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+mod arithmetic;
+mod blocking;
+#[cfg(feature = "futures")]
+mod changes;
+mod closed;
+mod combine;
+mod edge;
+mod guard;
+mod milestones;
+mod ordering;
+mod rate;
+mod split;
+mod sync;
+mod value;
+mod view;
+mod wait;
+mod waiters;
+mod watermarks;
+mod weak;
+
+pub use arithmetic::{ArithmeticError, OverflowPolicy};
+pub use blocking::WaitTimeoutError;
+#[cfg(feature = "futures")]
+pub use changes::Changes;
+pub use closed::Closed;
+pub use combine::{wait_all, wait_any, WaitAll, WaitAny};
+use edge::Edge;
+pub use edge::WaitEdge;
+pub use guard::CounterGuard;
+use milestones::Tracker;
+pub use milestones::{MilestoneReached, Milestones, NextMilestone};
+pub use ordering::MemoryOrdering;
+use rate::Rate;
+pub use split::{CounterReader, CounterWriter};
+use sync::atomic::AtomicUsize;
+use sync::Mutex;
+pub use value::{Atomic, Value};
+pub use view::{CounterView, ViewWaitFor, ViewWaitUntil};
+use wait::Target;
+pub use wait::{Changed, OwnedWaitFor, Take, WaitAtMost, WaitFor, WaitUntil};
+use waiters::Waiters;
+use watermarks::Gate;
+pub use watermarks::{GateState, WaitGate, Watermarks};
+pub use weak::WeakCounter;
 
 /// Globally available counter with a defined target.
-#[derive(Debug, Clone)]
-pub struct Counter {
-    value: Arc<AtomicUsize>,
-    target: usize,
-    waker: Arc<Mutex<Option<Waker>>>,
+///
+/// Awaiting a [Counter] waits for its `target`, which may be changed at any time
+/// by [Counter::set_target], while [Counter::wait_for] waits for an arbitrary one.
+/// Any number of futures may be pending on the same [Counter],
+/// each of them is woken whenever the value changes.
+///
+/// The value is a `usize` by default, any other [Value] type is supported,
+/// e.g., `Counter<u32>` for 32-bit targets or [SignedCounter] which may go below zero.
+///
+/// By default, a future resolving upon a modification of the value synchronizes with it,
+/// see [MemoryOrdering].
+///
+/// Once the [Counter] is closed, either by [Counter::close] or by dropping its last handle,
+/// the futures which are not satisfied yet fail with [Closed] instead of waiting forever.
+#[derive(Debug)]
+pub struct Counter<T: Value = usize> {
+    inner: Arc<Inner<T>>,
 }
 
-impl Counter {
-    const MUST_LOCK: &'static str = "Counter inner mutex must lock";
+/// [Counter] whose value may become negative.
+pub type SignedCounter = Counter<isize>;
 
+/// State shared by all clones of a [Counter] and the futures awaiting it.
+#[derive(Debug)]
+pub(crate) struct Inner<T: Value> {
+    value: T::Atomic,
+    target: T::Atomic,
+    /// Configuration is not a part of the synchronization, thus it is never modelled by loom.
+    policy: AtomicU8,
+    ordering: AtomicU8,
+    /// Rate tracker enabled by [Counter::with_rate_window], it is not modelled by loom either.
+    rate: OnceLock<Rate>,
+    /// Number of [Counter] handles, the last one closes the counter on drop.
+    writers: AtomicUsize,
+    waiters: Mutex<Waiters<T>>,
+}
+
+impl Counter {
     /// Create a [Counter] starting at `from` with `target`.
     pub fn new(from: usize, target: usize) -> Self {
-        Self {
-            value: Arc::new(AtomicUsize::new(from)),
-            target,
-            waker: Arc::new(Mutex::new(None)),
-        }
+        Self::with_value(from, target)
     }
 
     /// Create a [Counter] starting at 0 with `target`.
     pub fn to(target: usize) -> Self {
-        Self::new(0, target)
+        Self::with_target(target)
+    }
+}
+
+impl<T: Value> Counter<T> {
+    /// Create a [Counter] of any [Value] type starting at `from` with `target`.
+    pub fn with_value(from: T, target: T) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                value: T::Atomic::new(from),
+                target: T::Atomic::new(target),
+                policy: AtomicU8::new(OverflowPolicy::default().into_u8()),
+                ordering: AtomicU8::new(MemoryOrdering::default().into_u8()),
+                rate: OnceLock::new(),
+                writers: AtomicUsize::new(1),
+                waiters: Mutex::new(Waiters::default()),
+            }),
+        }
+    }
+
+    /// Create a [Counter] of any [Value] type starting at 0 with `target`.
+    pub fn with_target(target: T) -> Self {
+        Self::with_value(T::ZERO, target)
+    }
+
+    /// Set the [OverflowPolicy] of the operators, shared by all clones of this [Counter].
+    pub fn with_policy(self, policy: OverflowPolicy) -> Self {
+        self.inner.policy.store(policy.into_u8(), Ordering::Relaxed);
+        self
+    }
+
+    /// Fetch the [OverflowPolicy] of the operators.
+    pub fn policy(&self) -> OverflowPolicy {
+        self.inner.policy()
+    }
+
+    /// Set the [MemoryOrdering] of the operations on the value, shared by all clones of this [Counter].
+    pub fn with_ordering(self, ordering: MemoryOrdering) -> Self {
+        self.inner
+            .ordering
+            .store(ordering.into_u8(), Ordering::Relaxed);
+        self
+    }
+
+    /// Fetch the [MemoryOrdering] of the operations on the value.
+    pub fn ordering(&self) -> MemoryOrdering {
+        self.inner.ordering()
+    }
+
+    /// Track the rate of the modifications of the value over a sliding `window`,
+    /// shared by all clones of this [Counter].
+    ///
+    /// The value is sampled at most 16 times per `window`, by the first modification
+    /// in each sixteenth of it, so tracking costs neither memory nor locks per modification.
+    pub fn with_rate_window(self, window: Duration) -> Self {
+        let rate = self
+            .inner
+            .rate
+            .get_or_init(|| Rate::new(window, Instant::now(), self.value().as_f64()));
+        rate.set_window(window);
+        self
+    }
+
+    /// Gate the producers of this [Counter] by `watermarks`, shared by all its clones.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the low watermark exceeds the high one.
+    pub fn with_watermarks(self, watermarks: Watermarks<T>) -> Self {
+        let mut waiters = self.inner.waiters.lock().expect(Inner::<T>::MUST_LOCK);
+        waiters.set_gate(Gate::new(watermarks, self.value()));
+        let wakers = waiters.take_all();
+        drop(waiters);
+        Inner::<T>::wake(wakers);
+        self
+    }
+
+    /// Fetch the state of the gate, or `None` if the [Counter] has no [Watermarks].
+    pub fn gate_state(&self) -> Option<GateState> {
+        self.inner.gate_state()
+    }
+
+    /// Create a future resolving once the gate is in `state`.
+    ///
+    /// Awaiting both states in turn follows every change of the gate.
+    /// A [Counter] without [Watermarks] is always [GateState::Open].
+    pub fn wait_gate(&self, state: GateState) -> WaitGate<'_, T> {
+        WaitGate::new(&self.inner, state)
+    }
+
+    /// Create a future resolving once producers may proceed,
+    /// that is immediately unless the gate is [GateState::Paused].
+    pub fn wait_writable(&self) -> WaitGate<'_, T> {
+        self.wait_gate(GateState::Open)
+    }
+
+    /// Fetch the change of the value per second over the window given to [Counter::with_rate_window],
+    /// or `None` if the rate is not tracked.
+    pub fn rate(&self) -> Option<f64> {
+        self.inner.rate()
+    }
+
+    /// Estimate the time until the value reaches the `target` at the current [Counter::rate],
+    /// or `None` if the rate is not tracked or the value does not approach the `target`.
+    pub fn eta(&self) -> Option<Duration> {
+        self.inner.eta()
+    }
+
+    /// Fetch the value as a fraction of the `target`.
+    pub fn progress(&self) -> f64 {
+        self.inner.progress()
     }
 
     /// Fetch the current [Counter] value.
-    pub fn value(&self) -> usize {
-        self.value.load(Ordering::Relaxed)
+    pub fn value(&self) -> T {
+        self.inner.value()
     }
 
     /// Fetch the `target` [Counter] value.
-    pub fn target(&self) -> usize {
-        self.target
+    pub fn target(&self) -> T {
+        self.inner.target()
+    }
+
+    /// Replace the `target` shared by all clones of this [Counter],
+    /// waking all waiters so that they re-check the value against the new `target`.
+    pub fn set_target(&self, target: T) {
+        self.inner
+            .target
+            .store(target, self.inner.ordering().store());
+        self.inner.wake_all();
+    }
+
+    /// Create a future resolving once the [Counter] value is at least its `target`.
+    ///
+    /// Unlike [Counter::wait_for], the future follows changes made by [Counter::set_target].
+    pub fn wait(&self) -> WaitFor<'_, T> {
+        WaitFor::new(&self.inner, Target::Shared)
+    }
+
+    /// Create a future resolving once the [Counter] value is at least `target`.
+    ///
+    /// The future borrows the [Counter], so a single instance can serve many different targets.
+    pub fn wait_for(&self, target: T) -> WaitFor<'_, T> {
+        WaitFor::new(&self.inner, Target::Fixed(target))
+    }
+
+    /// Create a `'static` future resolving once the [Counter] value is at least `target`.
+    pub fn wait_for_owned(&self, target: T) -> OwnedWaitFor<T> {
+        OwnedWaitFor::new(self.inner.clone(), Target::Fixed(target))
+    }
+
+    /// Block the current thread until the [Counter] value is at least `target`.
+    ///
+    /// Meant for threads which cannot `.await`, blocked threads are woken
+    /// by the same modifications as the futures pending on the [Counter].
+    pub fn wait_blocking(&self, target: T) -> Result<T, Closed<T>> {
+        self.inner.wait_blocking(target)
+    }
+
+    /// Block the current thread until the [Counter] value is at least `target`
+    /// for at most `timeout`.
+    pub fn wait_blocking_timeout(
+        &self,
+        target: T,
+        timeout: Duration,
+    ) -> Result<T, WaitTimeoutError<T>> {
+        self.inner.wait_blocking_timeout(target, timeout)
+    }
+
+    /// Create a future resolving once the [Counter] value is at most `bound`.
+    pub fn wait_at_most(&self, bound: T) -> WaitAtMost<'_, T> {
+        WaitAtMost::new(&self.inner, bound)
+    }
+
+    /// Create a future resolving once the [Counter] value drops to 0 or below.
+    pub fn wait_zero(&self) -> WaitAtMost<'_, T> {
+        self.wait_at_most(T::ZERO)
+    }
+
+    /// Create a future resolving once `predicate` holds for the [Counter] value.
+    ///
+    /// `predicate` is re-evaluated every time the value changes.
+    pub fn wait_until<F>(&self, predicate: F) -> WaitUntil<'_, F, T>
+    where
+        F: FnMut(T) -> bool,
+    {
+        WaitUntil::new(&self.inner, predicate)
+    }
+
+    /// Create a future resolving once the [Counter] value rises from below `threshold`
+    /// to at least `threshold`.
+    ///
+    /// Unlike [Counter::wait_for], the future does not resolve if the value is already high,
+    /// only a later modification crossing `threshold` resolves it.
+    pub fn wait_rising(&self, threshold: T) -> WaitEdge<'_, T> {
+        WaitEdge::new(&self.inner, threshold, true)
+    }
+
+    /// Create a future resolving once the [Counter] value falls from at least `threshold`
+    /// to below `threshold`.
+    pub fn wait_falling(&self, threshold: T) -> WaitEdge<'_, T> {
+        WaitEdge::new(&self.inner, threshold, false)
+    }
+
+    /// Fetch the version of the [Counter] value, incremented by every modification.
+    pub fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    /// Create a future resolving with the current version and value
+    /// as soon as the [Counter] value is modified after the version `since`.
+    ///
+    /// Passing the version returned by the future back to [Counter::changed]
+    /// reacts to every modification without missing any of them,
+    /// although modifications made in between are coalesced.
+    pub fn changed(&self, since: u64) -> Changed<'_, T> {
+        Changed::new(&self.inner, since)
     }
 
-    /// Inner function incrementing the [Counter] value and waking a waker if any.
-    fn inc(&self, rhs: usize) {
-        self.value.fetch_add(rhs, Ordering::Relaxed);
-        if let Some(waker) = self.waker.lock().expect(Self::MUST_LOCK).take() {
-            waker.wake()
+    /// Create a future waiting until the [Counter] value is at least `n`
+    /// and subtracting `n` from it atomically, resolving with the value left.
+    ///
+    /// Takers are served in the order they are first polled, so a large take is not starved
+    /// by smaller ones. Unlike the operators, taking ignores the [OverflowPolicy].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is negative.
+    pub fn take(&self, n: T) -> Take<'_, T> {
+        assert!(n >= T::ZERO, "Counter cannot take a negative amount");
+        Take::new(&self.inner, n)
+    }
+
+    /// Subtract `n` from the [Counter] value if it is at least `n`
+    /// and no [Take] future is waiting, returning the value left.
+    ///
+    /// A negative `n` is never taken.
+    pub fn try_take(&self, n: T) -> Option<T> {
+        if n < T::ZERO {
+            return None;
         }
+        self.inner.try_take(n)
+    }
+
+    /// Create a stream of [MilestoneReached] events, one for each of `thresholds`
+    /// as soon as the [Counter] value reaches it.
+    ///
+    /// Milestones the value has already reached are delivered right away.
+    pub fn milestones(&self, thresholds: impl IntoIterator<Item = T>) -> Milestones<T> {
+        Milestones::new(self.inner.clone(), thresholds)
+    }
+
+    /// Create a stream of the [Counter] values, starting with the current one.
+    ///
+    /// Like a watch channel, the stream coalesces modifications made while it is not polled
+    /// and always yields the latest value.
+    #[cfg(feature = "futures")]
+    pub fn changes(&self) -> Changes<T> {
+        Changes::new(self.inner.clone())
+    }
+
+    /// Add `rhs` to the [Counter] value returning the new value,
+    /// or leave the value unchanged if the result overflows.
+    pub fn try_add(&self, rhs: T) -> Result<T, ArithmeticError> {
+        self.inner
+            .update(|value| value.checked_add(rhs))
+            .map(|prev| prev + rhs)
+            .map_err(|_| ArithmeticError::Overflow)
+    }
+
+    /// Subtract `rhs` from the [Counter] value returning the new value,
+    /// or leave the value unchanged if the result underflows.
+    pub fn try_sub(&self, rhs: T) -> Result<T, ArithmeticError> {
+        self.inner
+            .update(|value| value.checked_sub(rhs))
+            .map(|prev| prev - rhs)
+            .map_err(|_| ArithmeticError::Underflow)
+    }
+
+    /// Add `rhs` to the [Counter] value clamping at the maximum, returning the new value.
+    pub fn saturating_add(&self, rhs: T) -> T {
+        let prev = self.inner.saturating_add(rhs);
+        prev.saturating_add(rhs)
+    }
+
+    /// Subtract `rhs` from the [Counter] value clamping at the minimum, returning the new value.
+    pub fn saturating_sub(&self, rhs: T) -> T {
+        let prev = self.inner.saturating_sub(rhs);
+        prev.saturating_sub(rhs)
+    }
+
+    /// Close the [Counter], failing every future which is not satisfied yet with [Closed].
+    ///
+    /// The value may still be modified, yet futures resolve only if it already satisfies them.
+    pub fn close(&self) {
+        self.inner.close();
+    }
+
+    /// Fetch the number of handles able to modify the [Counter] value.
+    pub fn writer_count(&self) -> usize {
+        self.inner.writers.load(Ordering::Acquire)
+    }
+
+    /// Check whether the [Counter] is closed.
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
     }
 
-    /// Inner function decrementing the [Counter] value and waking a waker if any.
-    fn dec(&self, rhs: usize) {
-        self.value.fetch_sub(rhs, Ordering::Relaxed);
-        if let Some(waker) = self.waker.lock().expect(Self::MUST_LOCK).take() {
-            waker.wake()
+    /// Increment the [Counter] value by 1 until the returned guard is dropped.
+    pub fn guard(&self) -> CounterGuard<T> {
+        self.guard_n(T::ONE)
+    }
+
+    /// Increment the [Counter] value by `n` until the returned guard is dropped.
+    pub fn guard_n(&self, n: T) -> CounterGuard<T> {
+        CounterGuard::new(self.clone(), n)
+    }
+
+    /// Add `rhs` to the [Counter] value according to the [OverflowPolicy], returning the previous value.
+    pub fn fetch_add(&self, rhs: T) -> T {
+        self.inner.inc(rhs)
+    }
+
+    /// Subtract `rhs` from the [Counter] value according to the [OverflowPolicy], returning the previous value.
+    pub fn fetch_sub(&self, rhs: T) -> T {
+        self.inner.dec(rhs)
+    }
+
+    /// Replace the [Counter] value with `value`, returning the previous value.
+    pub fn swap(&self, value: T) -> T {
+        let prev = self.inner.value.swap(value, self.inner.ordering().update());
+        self.inner.notify(prev, value);
+        prev
+    }
+
+    /// Replace the [Counter] value with `new` if it equals `current`.
+    ///
+    /// Returns the previous value on success, otherwise the current value as an error.
+    pub fn compare_exchange(&self, current: T, new: T) -> Result<T, T> {
+        let ordering = self.inner.ordering();
+        let result =
+            self.inner
+                .value
+                .compare_exchange(current, new, ordering.update(), ordering.load());
+        if result.is_ok() {
+            self.inner.notify(current, new);
         }
+        result
+    }
+
+    /// Replace the [Counter] value with the result of `f` if any, retrying on contention.
+    ///
+    /// Returns the previous value if `f` produced a new one, otherwise the current value as an error.
+    pub fn fetch_update<F>(&self, f: F) -> Result<T, T>
+    where
+        F: FnMut(T) -> Option<T>,
+    {
+        self.inner.update(f)
     }
 
-    /// Inner function setting the [Counter] value and waking a waker if any.
+    /// Inner function setting the [Counter] value and waking all waiters.
     ///
     /// Operator `=` has no backing trait, thus this method must be exposed directly.
-    pub fn set(&self, rhs: usize) {
-        self.value.store(rhs, Ordering::Relaxed);
+    pub fn set(&self, rhs: T) {
+        // The previous value is needed to detect the edges crossed by the modification.
+        let prev = self.inner.value.swap(rhs, self.inner.ordering().update());
+        self.inner.notify(prev, rhs);
+    }
+}
+
+impl<T: Value> Inner<T> {
+    const MUST_LOCK: &'static str = "Counter inner mutex must lock";
 
-        if let Some(waker) = self.waker.lock().expect(Self::MUST_LOCK).take() {
-            waker.wake()
+    fn value(&self) -> T {
+        self.value.load(self.ordering().load())
+    }
+
+    pub(crate) fn target(&self) -> T {
+        self.target.load(self.ordering().load())
+    }
+
+    fn rate(&self) -> Option<f64> {
+        let rate = self.rate.get()?;
+        rate.per_second(Instant::now(), self.value().as_f64())
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        let remaining = self.target().as_f64() - self.value().as_f64();
+        if remaining <= 0.0 {
+            return Some(Duration::ZERO);
         }
+        let rate = self.rate()?;
+        (rate > 0.0).then(|| Duration::from_secs_f64(remaining / rate))
+    }
+
+    fn progress(&self) -> f64 {
+        let target = self.target().as_f64();
+        if target == 0.0 {
+            1.0
+        } else {
+            self.value().as_f64() / target
+        }
+    }
+
+    /// Inner function sampling the `new` value for the rate tracker if enabled.
+    fn record(&self, new: T) {
+        if let Some(rate) = self.rate.get() {
+            rate.record(Instant::now(), new.as_f64());
+        }
+    }
+
+    fn version(&self) -> u64 {
+        self.waiters.lock().expect(Self::MUST_LOCK).version()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.waiters.lock().expect(Self::MUST_LOCK).is_closed()
+    }
+
+    fn gate_state(&self) -> Option<GateState> {
+        self.waiters.lock().expect(Self::MUST_LOCK).gate_state()
+    }
+
+    fn wait_blocking(&self, target: T) -> Result<T, Closed<T>> {
+        blocking::block_on(WaitFor::new(self, Target::Fixed(target)), None)
+            .expect("Waiting without a deadline always completes")
+    }
+
+    fn wait_blocking_timeout(
+        &self,
+        target: T,
+        timeout: Duration,
+    ) -> Result<T, WaitTimeoutError<T>> {
+        let deadline = Instant::now() + timeout;
+        match blocking::block_on(WaitFor::new(self, Target::Fixed(target)), Some(deadline)) {
+            Some(r) => r.map_err(WaitTimeoutError::from),
+            None => Err(WaitTimeoutError::Timeout {
+                value: self.value(),
+            }),
+        }
+    }
+
+    fn ordering(&self) -> MemoryOrdering {
+        MemoryOrdering::from_u8(self.ordering.load(Ordering::Relaxed))
+    }
+
+    fn policy(&self) -> OverflowPolicy {
+        OverflowPolicy::from_u8(self.policy.load(Ordering::Relaxed))
+    }
+
+    /// Inner function incrementing the [Counter] value according to the [OverflowPolicy]
+    /// and waking all waiters, returns the previous value.
+    fn inc(&self, rhs: T) -> T {
+        match self.policy() {
+            OverflowPolicy::Wrap => {
+                let prev = self.value.fetch_add(rhs, self.ordering().update());
+                self.notify(prev, prev.wrapping_add(rhs));
+                prev
+            }
+            OverflowPolicy::Saturate => self.saturating_add(rhs),
+            OverflowPolicy::Panic => match self.update(|value| value.checked_add(rhs)) {
+                Ok(prev) => prev,
+                Err(_) => panic!("{}", ArithmeticError::Overflow),
+            },
+        }
+    }
+
+    /// Inner function decrementing the [Counter] value according to the [OverflowPolicy]
+    /// and waking all waiters, returns the previous value.
+    fn dec(&self, rhs: T) -> T {
+        match self.policy() {
+            OverflowPolicy::Wrap => {
+                let prev = self.value.fetch_sub(rhs, self.ordering().update());
+                self.notify(prev, prev.wrapping_sub(rhs));
+                prev
+            }
+            OverflowPolicy::Saturate => self.saturating_sub(rhs),
+            OverflowPolicy::Panic => match self.update(|value| value.checked_sub(rhs)) {
+                Ok(prev) => prev,
+                Err(_) => panic!("{}", ArithmeticError::Underflow),
+            },
+        }
+    }
+
+    /// Inner function adding `rhs` clamping at the maximum, returning the previous value.
+    fn saturating_add(&self, rhs: T) -> T {
+        self.update(|value| Some(value.saturating_add(rhs)))
+            .expect("Saturating addition always succeeds")
+    }
+
+    /// Inner function subtracting `rhs` clamping at the minimum, returning the previous value.
+    fn saturating_sub(&self, rhs: T) -> T {
+        self.update(|value| Some(value.saturating_sub(rhs)))
+            .expect("Saturating subtraction always succeeds")
+    }
+
+    /// Inner function replacing the value with the result of `f` if any and waking all waiters.
+    ///
+    /// Returns the previous value if `f` produced a new one, otherwise the current value as an error.
+    fn update(&self, mut f: impl FnMut(T) -> Option<T>) -> Result<T, T> {
+        let ordering = self.ordering();
+        // The value produced by the last call of `f` is the one stored.
+        let mut new = None;
+        let result = self
+            .value
+            .fetch_update(ordering.update(), ordering.load(), |value| {
+                new = f(value);
+                new
+            });
+        if let (Ok(prev), Some(new)) = (result, new) {
+            self.notify(prev, new);
+        }
+        result
+    }
+
+    /// Inner function recording a modification of the value from `prev` to `new`
+    /// and waking every future pending on the [Counter].
+    fn notify(&self, prev: T, new: T) {
+        self.record(new);
+        let mut waiters = self.waiters.lock().expect(Self::MUST_LOCK);
+        let wakers = waiters.notify(prev, new, self.value());
+        drop(waiters);
+        Self::wake(wakers);
+    }
+
+    /// Inner function waking every future pending on the [Counter] without modifying the value.
+    fn wake_all(&self) {
+        let wakers = self.waiters.lock().expect(Self::MUST_LOCK).take_all();
+        Self::wake(wakers);
+    }
+
+    /// Inner function closing the [Counter] and waking every future pending on it.
+    fn close(&self) {
+        let wakers = self.waiters.lock().expect(Self::MUST_LOCK).close();
+        Self::wake(wakers);
+    }
+
+    /// Wakers are collected under the lock and woken after it is released.
+    fn wake(wakers: Vec<Waker>) {
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Resolve with the current value if it satisfies `ready`, fail if the [Counter] is closed,
+    /// otherwise register the waker of `cx` in the slot identified by `key`.
+    ///
+    /// The value is checked again once the waker is registered: modifications wake waiters
+    /// only after acquiring the same lock, thus a modification racing with the registration
+    /// is either observed by the second check or wakes the registered waker.
+    /// `ready` is user code, so it never runs under the lock.
+    pub(crate) fn poll_until(
+        &self,
+        key: &mut Option<usize>,
+        cx: &mut Context<'_>,
+        mut ready: impl FnMut(T) -> bool,
+    ) -> Poll<Result<T, Closed<T>>> {
+        let value = self.value();
+        if ready(value) {
+            return Poll::Ready(Ok(value));
+        }
+
+        let closed = self.register(key, cx);
+        let value = self.value();
+        if ready(value) {
+            Poll::Ready(Ok(value))
+        } else if closed {
+            Poll::Ready(Err(Closed { value }))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Resolve with the current version and value if the value has been modified
+    /// since the version `since`, or right away if `since` is `None`.
+    /// Otherwise fail if the [Counter] is closed or register the waker of `cx` in the slot identified by `key`.
+    ///
+    /// Modifications are counted under the same lock, so none of them can be missed,
+    /// while several of them may be coalesced into a single one.
+    pub(crate) fn poll_changed(
+        &self,
+        key: &mut Option<usize>,
+        cx: &mut Context<'_>,
+        since: Option<u64>,
+    ) -> Poll<Result<(u64, T), Closed<T>>> {
+        let mut waiters = self.waiters.lock().expect(Self::MUST_LOCK);
+        let version = waiters.version();
+        if since != Some(version) {
+            Poll::Ready(Ok((version, self.value())))
+        } else if waiters.is_closed() {
+            Poll::Ready(Err(Closed {
+                value: self.value(),
+            }))
+        } else {
+            waiters.register(key, cx.waker());
+            Poll::Pending
+        }
+    }
+
+    /// Subtract `n` from the value if it is at least `n`, returning the previous and the new value.
+    ///
+    /// Must be called under the lock of the waiters to keep the order of the takers.
+    fn take(&self, n: T) -> Result<(T, T), T> {
+        let ordering = self.ordering();
+        // The value produced by the last attempt is the one stored.
+        let mut new = None;
+        let prev = self
+            .value
+            .fetch_update(ordering.update(), ordering.load(), |value| {
+                new = value.checked_sub(n).filter(|_| value >= n);
+                new
+            })?;
+        Ok((prev, new.expect("Taken value is computed")))
+    }
+
+    /// Inner function taking `n` from the value unless takers are waiting.
+    fn try_take(&self, n: T) -> Option<T> {
+        let mut waiters = self.waiters.lock().expect(Self::MUST_LOCK);
+        if !waiters.is_next_taker(None) {
+            return None;
+        }
+
+        let (prev, new) = self.take(n).ok()?;
+        let wakers = waiters.notify(prev, new, self.value());
+        drop(waiters);
+        self.record(new);
+        Self::wake(wakers);
+        Some(new)
+    }
+
+    /// Take `n` from the value if the taker identified by `key` is the first in the queue,
+    /// fail if it cannot or is not the first one and the [Counter] is closed,
+    /// otherwise queue the taker and register the waker of `cx`.
+    pub(crate) fn poll_take(
+        &self,
+        key: &mut Option<usize>,
+        cx: &mut Context<'_>,
+        n: T,
+    ) -> Poll<Result<T, Closed<T>>> {
+        let mut waiters = self.waiters.lock().expect(Self::MUST_LOCK);
+        waiters.register(key, cx.waker());
+        let k = key.expect("Registered waiter has a key");
+        waiters.enqueue(k);
+        // Takers behind the first one only fail, once the counter is closed.
+        let taken = if waiters.is_next_taker(Some(k)) {
+            self.take(n)
+        } else {
+            Err(self.value())
+        };
+
+        // Released slot must not be woken by the modification below.
+        let (result, wakers) = match taken {
+            Ok((prev, new)) => {
+                waiters.remove(k);
+                (Ok(new), waiters.notify(prev, new, self.value()))
+            }
+            Err(value) if waiters.is_closed() => {
+                waiters.remove(k);
+                (Err(Closed { value }), waiters.take_all())
+            }
+            Err(_) => return Poll::Pending,
+        };
+        // The next taker is woken either way.
+        waiters.dequeue(k);
+        *key = None;
+        drop(waiters);
+        if let Ok(new) = result {
+            self.record(new);
+        }
+        Self::wake(wakers);
+        Poll::Ready(result)
+    }
+
+    /// Remove the taker identified by `key` from the queue if any,
+    /// waking the next taker if the removed one was first.
+    pub(crate) fn cancel_take(&self, key: Option<usize>) {
+        if let Some(key) = key {
+            let mut waiters = self.waiters.lock().expect(Self::MUST_LOCK);
+            if waiters.dequeue(key) {
+                let wakers = waiters.take_all();
+                drop(waiters);
+                Self::wake(wakers);
+            }
+        }
+    }
+
+    /// Start tracking `thresholds` against every modification of the value.
+    pub(crate) fn add_milestones(&self, thresholds: impl IntoIterator<Item = T>) -> usize {
+        let mut waiters = self.waiters.lock().expect(Self::MUST_LOCK);
+        let tracker = Tracker::new(thresholds, self.value());
+        waiters.add_milestones(tracker)
     }
-}
 
-impl Future for Counter {
-    type Output = usize;
+    /// Resolve with the next milestone reached by the tracker identified by `milestones`,
+    /// or with `None` once all of them are delivered or the [Counter] is closed.
+    /// Otherwise register the waker of `cx` in the slot identified by `key`.
+    pub(crate) fn poll_milestone(
+        &self,
+        key: &mut Option<usize>,
+        cx: &mut Context<'_>,
+        milestones: usize,
+    ) -> Poll<Option<MilestoneReached<T>>> {
+        let mut waiters = self.waiters.lock().expect(Self::MUST_LOCK);
+        match waiters.next_milestone(milestones) {
+            Ok(event) => Poll::Ready(event),
+            Err(()) if waiters.is_closed() => Poll::Ready(None),
+            Err(()) => {
+                waiters.register(key, cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Stop tracking the milestones identified by `milestones`.
+    pub(crate) fn remove_milestones(&self, milestones: usize) {
+        self.waiters
+            .lock()
+            .expect(Self::MUST_LOCK)
+            .remove_milestones(milestones);
+    }
+
+    /// Start watching `edge` against every modification of the value.
+    pub(crate) fn add_edge(&self, edge: Edge<T>) -> usize {
+        self.waiters.lock().expect(Self::MUST_LOCK).add_edge(edge)
+    }
+
+    /// Resolve with the value which crossed the edge identified by `edge`,
+    /// fail if the edge is not crossed and the [Counter] is closed,
+    /// otherwise register the waker of `cx` in the slot identified by `key`.
+    pub(crate) fn poll_edge(
+        &self,
+        key: &mut Option<usize>,
+        cx: &mut Context<'_>,
+        edge: usize,
+    ) -> Poll<Result<T, Closed<T>>> {
+        let mut waiters = self.waiters.lock().expect(Self::MUST_LOCK);
+        match waiters.edge_crossed(edge) {
+            Some(value) => Poll::Ready(Ok(value)),
+            None if waiters.is_closed() => Poll::Ready(Err(Closed {
+                value: self.value(),
+            })),
+            None => {
+                waiters.register(key, cx.waker());
+                Poll::Pending
+            }
+        }
+    }
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let value = self.value.load(Ordering::Relaxed);
-        if value >= self.target {
-            Poll::Ready(value)
+    /// Resolve with the current value if the gate is in `state`,
+    /// fail if the [Counter] is closed,
+    /// otherwise register the waker of `cx` in the slot identified by `key`.
+    pub(crate) fn poll_gate(
+        &self,
+        key: &mut Option<usize>,
+        cx: &mut Context<'_>,
+        state: GateState,
+    ) -> Poll<Result<T, Closed<T>>> {
+        let mut waiters = self.waiters.lock().expect(Self::MUST_LOCK);
+        let value = self.value();
+        if waiters.gate_state().unwrap_or(GateState::Open) == state {
+            Poll::Ready(Ok(value))
+        } else if waiters.is_closed() {
+            Poll::Ready(Err(Closed { value }))
         } else {
-            *self.waker.lock().expect(Self::MUST_LOCK) = Some(cx.waker().clone());
+            waiters.register(key, cx.waker());
             Poll::Pending
         }
     }
+
+    /// Stop watching the edge identified by `edge`.
+    pub(crate) fn remove_edge(&self, edge: usize) {
+        self.waiters
+            .lock()
+            .expect(Self::MUST_LOCK)
+            .remove_edge(edge);
+    }
+
+    /// Register the waker of `cx` in the slot identified by `key`,
+    /// returns whether the [Counter] is closed.
+    pub(crate) fn register(&self, key: &mut Option<usize>, cx: &mut Context<'_>) -> bool {
+        let mut waiters = self.waiters.lock().expect(Self::MUST_LOCK);
+        waiters.register(key, cx.waker());
+        waiters.is_closed()
+    }
+
+    /// Release the slot identified by `key` if any.
+    pub(crate) fn deregister(&self, key: Option<usize>) {
+        if let Some(key) = key {
+            self.waiters.lock().expect(Self::MUST_LOCK).remove(key);
+        }
+    }
 }
 
-impl AddAssign<usize> for Counter {
-    fn add_assign(&mut self, rhs: usize) {
-        self.inc(rhs);
+impl<T: Value> Clone for Counter<T> {
+    fn clone(&self) -> Self {
+        self.inner.writers.fetch_add(1, Ordering::Relaxed);
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Value> Drop for Counter<T> {
+    fn drop(&mut self) {
+        if self.inner.writers.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.inner.close();
+        }
+    }
+}
+
+impl<T: Value> IntoFuture for Counter<T> {
+    type Output = Result<T, Closed<T>>;
+    type IntoFuture = OwnedWaitFor<T>;
+
+    /// The awaiting future does not keep the [Counter] open.
+    fn into_future(self) -> Self::IntoFuture {
+        OwnedWaitFor::new(self.inner.clone(), Target::Shared)
     }
 }
 
-impl SubAssign<usize> for Counter {
-    fn sub_assign(&mut self, rhs: usize) {
-        self.dec(rhs);
+impl<T: Value> AddAssign<T> for Counter<T> {
+    fn add_assign(&mut self, rhs: T) {
+        self.inner.inc(rhs);
     }
 }
 
-impl Add<usize> for Counter {
+impl<T: Value> SubAssign<T> for Counter<T> {
+    fn sub_assign(&mut self, rhs: T) {
+        self.inner.dec(rhs);
+    }
+}
+
+impl<T: Value> Add<T> for Counter<T> {
     type Output = Self;
 
-    fn add(mut self, rhs: usize) -> Self::Output {
+    fn add(mut self, rhs: T) -> Self::Output {
         self += rhs;
         self
     }
 }
 
-impl Sub<usize> for Counter {
+impl<T: Value> Sub<T> for Counter<T> {
     type Output = Self;
 
-    fn sub(mut self, rhs: usize) -> Self::Output {
+    fn sub(mut self, rhs: T) -> Self::Output {
         self -= rhs;
         self
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(loom)))]
 mod tests {
-    use crate::Counter;
+    use crate::{Counter, SignedCounter};
     use log::debug;
     use std::ops::Mul;
     use std::time::Duration;
     use tokio::time;
 
     #[tokio::test]
+    #[allow(clippy::assign_op_pattern)]
     async fn counter_counts_up() {
         let _ = pretty_env_logger::try_init();
 
@@ -142,7 +977,7 @@ mod tests {
 
         // Wait for the target to be satisfied.
         let r = time::timeout(counting_interval.mul(20), counter).await;
-        assert!(matches!(r, Ok(t) if t == target));
+        assert!(matches!(r, Ok(Ok(t)) if t == target));
 
         debug!("Counter target is reached!");
     }
@@ -175,7 +1010,7 @@ mod tests {
 
         // Wait for the target to be satisfied.
         let r = time::timeout(counting_interval.mul(20), counter.clone()).await;
-        assert!(matches!(r, Ok(t) if t == target));
+        assert!(matches!(r, Ok(Ok(t)) if t == target));
 
         // Give the child task time to decrement the counter.
         time::sleep(counting_interval.mul(2)).await;
@@ -183,8 +1018,70 @@ mod tests {
         // Wait for the target to be satisfied.
         let r = time::timeout(counting_interval.mul(20), counter).await;
         debug!("{r:?}");
-        assert!(matches!(r, Ok(t) if t == 12));
+        assert!(matches!(r, Ok(Ok(t)) if t == 12));
 
         debug!("Counter target is reached!");
     }
+
+    #[tokio::test]
+    async fn counter_wakes_all_waiters() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let target = 10;
+        let counter = Counter::to(target);
+        let mut count = counter.clone();
+
+        // Await the same counter from several tasks at once.
+        let waiters = (0..8)
+            .map(|_| tokio::spawn(time::timeout(counting_interval.mul(20), counter.clone())))
+            .collect::<Vec<_>>();
+
+        // Spawn a task to update the counter.
+        tokio::spawn(async move {
+            for i in 0u8..2 {
+                time::sleep(counting_interval).await;
+                debug!("Tick {i}");
+                count += 5;
+            }
+        });
+
+        for waiter in waiters {
+            let r = waiter.await.expect("Waiting task must not panic");
+            assert!(matches!(r, Ok(Ok(t)) if t == target));
+        }
+
+        debug!("Counter target is reached by all waiters!");
+    }
+
+    #[tokio::test]
+    async fn signed_counter_goes_negative() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = SignedCounter::with_target(5);
+        let mut count = counter.clone();
+
+        // Spawn a task to debit and then credit the counter.
+        tokio::spawn(async move {
+            for i in 0u8..3 {
+                time::sleep(counting_interval).await;
+                debug!("Debit {i}");
+                count -= 2;
+            }
+            // count = -6.
+
+            time::sleep(counting_interval).await;
+            count += 11;
+            // count = 5, future must have been triggered.
+        });
+
+        let r = time::timeout(counting_interval.mul(20), counter.wait_at_most(-6)).await;
+        assert!(matches!(r, Ok(Ok(-6))));
+
+        let r = time::timeout(counting_interval.mul(20), counter).await;
+        assert!(matches!(r, Ok(Ok(5))));
+    }
 }
@@ -0,0 +1,178 @@
+use crate::wait::Waiter;
+use crate::{Inner, Value};
+#[cfg(feature = "futures")]
+use futures_core::{FusedStream, Stream};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Event of the value of a [Counter](crate::Counter) reaching a milestone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MilestoneReached<T> {
+    /// Milestone which is reached.
+    pub threshold: T,
+    /// Value which reached the milestone first.
+    pub value: T,
+}
+
+/// Milestones of a single [Milestones] stream, updated under the lock of the waiters.
+#[derive(Debug)]
+pub(crate) struct Tracker<T> {
+    /// Thresholds which are not reached yet in ascending order.
+    ahead: VecDeque<T>,
+    /// Events which are not delivered yet.
+    reached: VecDeque<MilestoneReached<T>>,
+}
+
+impl<T: Value> Tracker<T> {
+    pub(crate) fn new(thresholds: impl IntoIterator<Item = T>, value: T) -> Self {
+        let mut ahead = thresholds.into_iter().collect::<Vec<_>>();
+        ahead.sort_unstable();
+        ahead.dedup();
+        let mut tracker = Self {
+            ahead: ahead.into(),
+            reached: VecDeque::new(),
+        };
+        tracker.observe(value);
+        tracker
+    }
+
+    /// Record every milestone which `value` reaches.
+    pub(crate) fn observe(&mut self, value: T) {
+        while let Some(&threshold) = self.ahead.front().filter(|&&t| t <= value) {
+            self.ahead.pop_front();
+            self.reached
+                .push_back(MilestoneReached { threshold, value });
+        }
+    }
+
+    /// Take the next event, `Ok(None)` if all of them are delivered, `Err(())` if some are still ahead.
+    pub(crate) fn next_event(&mut self) -> Result<Option<MilestoneReached<T>>, ()> {
+        match self.reached.pop_front() {
+            Some(event) => Ok(Some(event)),
+            None if self.ahead.is_empty() => Ok(None),
+            None => Err(()),
+        }
+    }
+}
+
+/// Events of the value of a [Counter](crate::Counter) reaching each of a set of milestones.
+///
+/// A milestone is reached once the value is at least its threshold, every milestone is delivered
+/// exactly once and in ascending order, even if a single modification jumps past several of them.
+/// The events end once all milestones are delivered or the counter is closed.
+/// Created by [Counter::milestones](crate::Counter::milestones).
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Milestones<T: Value = usize> {
+    waiter: Waiter<T, Arc<Inner<T>>>,
+    key: usize,
+}
+
+impl<T: Value> Milestones<T> {
+    pub(crate) fn new(inner: Arc<Inner<T>>, thresholds: impl IntoIterator<Item = T>) -> Self {
+        let key = inner.add_milestones(thresholds);
+        Self {
+            waiter: Waiter::new(inner),
+            key,
+        }
+    }
+
+    /// Create a future resolving with the next reached milestone,
+    /// or `None` once all milestones are delivered or the counter is closed.
+    pub fn next_reached(&mut self) -> NextMilestone<'_, T> {
+        NextMilestone { milestones: self }
+    }
+
+    fn poll_milestone(&mut self, cx: &mut Context<'_>) -> Poll<Option<MilestoneReached<T>>> {
+        self.waiter.poll_milestone(cx, self.key)
+    }
+}
+
+impl<T: Value> Drop for Milestones<T> {
+    fn drop(&mut self) {
+        self.waiter.remove_milestones(self.key);
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Value> Stream for Milestones<T> {
+    type Item = MilestoneReached<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_milestone(cx)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Value> FusedStream for Milestones<T> {
+    fn is_terminated(&self) -> bool {
+        self.waiter.is_done()
+    }
+}
+
+/// Future resolving with the next event of [Milestones].
+///
+/// Created by [Milestones::next_reached].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct NextMilestone<'a, T: Value = usize> {
+    milestones: &'a mut Milestones<T>,
+}
+
+impl<T: Value> Future for NextMilestone<'_, T> {
+    type Output = Option<MilestoneReached<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().milestones.poll_milestone(cx)
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::{Counter, MilestoneReached};
+    use std::ops::Mul;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn milestones_are_delivered_once() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let job = Counter::new(10, 100);
+        let mut count = job.clone();
+        let mut milestones = job.milestones([25, 50, 75, 100, 10]);
+
+        // A single jump past several milestones, going back does not repeat them.
+        tokio::spawn(async move {
+            time::sleep(counting_interval).await;
+            count += 70;
+            count -= 60;
+            time::sleep(counting_interval).await;
+            count += 90;
+        });
+
+        let mut events = Vec::new();
+        while let Ok(Some(event)) =
+            time::timeout(counting_interval.mul(20), milestones.next_reached()).await
+        {
+            events.push(event);
+        }
+
+        let reached = |threshold, value| MilestoneReached { threshold, value };
+        assert_eq!(
+            events,
+            [
+                reached(10, 10),
+                reached(25, 80),
+                reached(50, 80),
+                reached(75, 80),
+                reached(100, 110),
+            ]
+        );
+    }
+}
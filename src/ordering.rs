@@ -0,0 +1,99 @@
+use std::sync::atomic::Ordering;
+
+/// Memory ordering of the atomic operations on the value of a [Counter](crate::Counter).
+///
+/// With the default [MemoryOrdering::AcquireRelease], every modification of the value
+/// is a release operation and every check of the value is an acquire operation.
+/// Thus, everything written by a task before modifying the counter is visible
+/// to a task whose future resolved upon observing that modification,
+/// which makes the counter usable as a synchronization point, e.g., as a latch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MemoryOrdering {
+    /// No synchronization beyond the value itself.
+    Relaxed,
+    /// Modifications release, checks acquire.
+    #[default]
+    AcquireRelease,
+    /// Like [MemoryOrdering::AcquireRelease] with a single total order of all operations.
+    SequentiallyConsistent,
+}
+
+impl MemoryOrdering {
+    /// Ordering for loading the value.
+    pub(crate) fn load(self) -> Ordering {
+        match self {
+            Self::Relaxed => Ordering::Relaxed,
+            Self::AcquireRelease => Ordering::Acquire,
+            Self::SequentiallyConsistent => Ordering::SeqCst,
+        }
+    }
+
+    /// Ordering for storing the value.
+    pub(crate) fn store(self) -> Ordering {
+        match self {
+            Self::Relaxed => Ordering::Relaxed,
+            Self::AcquireRelease => Ordering::Release,
+            Self::SequentiallyConsistent => Ordering::SeqCst,
+        }
+    }
+
+    /// Ordering for read-modify-write operations on the value.
+    pub(crate) fn update(self) -> Ordering {
+        match self {
+            Self::Relaxed => Ordering::Relaxed,
+            Self::AcquireRelease => Ordering::AcqRel,
+            Self::SequentiallyConsistent => Ordering::SeqCst,
+        }
+    }
+
+    pub(crate) fn into_u8(self) -> u8 {
+        match self {
+            Self::Relaxed => 0,
+            Self::AcquireRelease => 1,
+            Self::SequentiallyConsistent => 2,
+        }
+    }
+
+    pub(crate) fn from_u8(ordering: u8) -> Self {
+        match ordering {
+            0 => Self::Relaxed,
+            1 => Self::AcquireRelease,
+            2 => Self::SequentiallyConsistent,
+            _ => unreachable!("Memory ordering is always stored from a valid variant"),
+        }
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::Counter;
+    use std::ops::Mul;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn wait_synchronizes_with_increment() {
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::to(1);
+        let mut count = counter.clone();
+        let data = Arc::new(AtomicUsize::new(0));
+        let written = data.clone();
+
+        // Publish data from a plain thread.
+        let writer = thread::spawn(move || {
+            thread::sleep(counting_interval);
+            written.store(42, Ordering::Relaxed);
+            count += 1;
+        });
+
+        let r = time::timeout(counting_interval.mul(20), counter.wait_for(1)).await;
+        assert!(matches!(r, Ok(Ok(1))));
+        assert_eq!(data.load(Ordering::Relaxed), 42);
+
+        writer.join().expect("Writer thread must not panic");
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Samples of the value of a [Counter](crate::Counter) over a sliding window.
+///
+/// At most one sample is kept per [Rate::BUCKETS]-th of the window, writes in between are skipped
+/// without locking, so neither memory nor locking grows with the write rate.
+/// The first sample is the latest one taken at or before the start of the window,
+/// so the rate of a stalled counter decays towards zero instead of becoming unknown.
+#[derive(Debug)]
+pub(crate) struct Rate {
+    origin: Instant,
+    /// Nanoseconds since `origin` from which the next sample is due.
+    due: AtomicU64,
+    samples: Mutex<Samples>,
+}
+
+#[derive(Debug)]
+struct Samples {
+    window: Duration,
+    samples: VecDeque<(Instant, f64)>,
+}
+
+impl Rate {
+    /// Number of samples per window.
+    pub(crate) const BUCKETS: u32 = 16;
+
+    const MUST_LOCK: &'static str = "Rate mutex must lock";
+
+    pub(crate) fn new(window: Duration, now: Instant, value: f64) -> Self {
+        let rate = Self {
+            origin: now,
+            due: AtomicU64::new(0),
+            samples: Mutex::new(Samples {
+                window,
+                samples: VecDeque::from([(now, value)]),
+            }),
+        };
+        rate.schedule(now, window);
+        rate
+    }
+
+    /// Replace the window, keeping the samples.
+    pub(crate) fn set_window(&self, window: Duration) {
+        self.samples.lock().expect(Self::MUST_LOCK).window = window;
+    }
+
+    /// Sample `value` unless the bucket of `now` is already sampled.
+    pub(crate) fn record(&self, now: Instant, value: f64) {
+        if self.nanos(now) < self.due.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let mut samples = self.samples.lock().expect(Self::MUST_LOCK);
+        samples.samples.push_back((now, value));
+        samples.prune(now);
+        self.schedule(now, samples.window);
+    }
+
+    /// Change of the value per second between the start of the window and `now`.
+    pub(crate) fn per_second(&self, now: Instant, value: f64) -> Option<f64> {
+        let mut samples = self.samples.lock().expect(Self::MUST_LOCK);
+        samples.prune(now);
+        let &(since, from) = samples.samples.front()?;
+        let elapsed = now.duration_since(since).as_secs_f64();
+        (elapsed > 0.0).then(|| (value - from) / elapsed)
+    }
+
+    fn schedule(&self, now: Instant, window: Duration) {
+        let due = self.nanos(now + window / Self::BUCKETS);
+        self.due.store(due, Ordering::Relaxed);
+    }
+
+    fn nanos(&self, now: Instant) -> u64 {
+        u64::try_from(now.saturating_duration_since(self.origin).as_nanos()).unwrap_or(u64::MAX)
+    }
+
+    #[cfg(all(test, not(loom)))]
+    fn len(&self) -> usize {
+        self.samples.lock().expect(Self::MUST_LOCK).samples.len()
+    }
+}
+
+impl Samples {
+    fn prune(&mut self, now: Instant) {
+        let Some(start) = now.checked_sub(self.window) else {
+            return;
+        };
+        while self.samples.len() > 1 && self.samples[1].0 <= start {
+            self.samples.pop_front();
+        }
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::Rate;
+    use crate::Counter;
+    use std::time::{Duration, Instant};
+    use tokio::time;
+
+    #[tokio::test]
+    async fn rate_estimates_eta() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::to(100);
+        assert_eq!(counter.rate(), None);
+        assert_eq!(counter.progress(), 0.0);
+
+        let counter = counter.with_rate_window(counting_interval * 100);
+        let mut count = counter.clone();
+        for _ in 0u8..5 {
+            time::sleep(counting_interval).await;
+            count += 10;
+        }
+
+        // 50 per 50ms at best, scheduling only slows it down.
+        let rate = counter.rate().expect("Rate is tracked");
+        assert!(rate > 0.0 && rate <= 1000.0, "{rate}");
+        assert_eq!(counter.progress(), 0.5);
+        let eta = counter.eta().expect("Counter makes progress");
+        assert!(eta >= counting_interval * 5, "{eta:?}");
+
+        count += 50;
+        assert_eq!(counter.eta(), Some(Duration::ZERO));
+        assert_eq!(counter.progress(), 1.0);
+    }
+
+    #[test]
+    fn samples_are_bounded_by_buckets() {
+        let window = Duration::from_millis(16);
+        let origin = Instant::now();
+        let rate = Rate::new(window, origin, 0.0);
+
+        // A write every microsecond over three windows.
+        for i in 1..48_000u32 {
+            rate.record(origin + Duration::from_micros(i.into()), i.into());
+        }
+
+        assert!(rate.len() <= Rate::BUCKETS as usize + 2, "{}", rate.len());
+        let now = origin + window * 3;
+        let per_second = rate.per_second(now, 48_000.0).expect("Rate is tracked");
+        assert!((per_second - 1_000_000.0).abs() < 100_000.0, "{per_second}");
+    }
+}
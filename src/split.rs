@@ -0,0 +1,383 @@
+use crate::wait::Target;
+#[cfg(feature = "futures")]
+use crate::Changes;
+use crate::{
+    ArithmeticError, Changed, Closed, Counter, CounterGuard, GateState, Inner, Milestones,
+    OwnedWaitFor, Value, WaitAtMost, WaitEdge, WaitFor, WaitGate, WaitTimeoutError, WaitUntil,
+};
+use std::future::IntoFuture;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Handle of a [Counter] which can only observe and await the value.
+///
+/// A reader does not keep the counter open: once all writing handles are dropped,
+/// the futures of the reader which are not satisfied yet fail with [Closed].
+/// Created by [Counter::reader] and [Counter::split].
+#[derive(Debug, Clone)]
+pub struct CounterReader<T: Value = usize> {
+    inner: Arc<Inner<T>>,
+}
+
+/// Handle of a [Counter] which can only modify the value.
+///
+/// Every writer counts as a producer, the counter is closed once the last one is dropped.
+/// Created by [Counter::split].
+#[derive(Debug, Clone)]
+pub struct CounterWriter<T: Value = usize> {
+    counter: Counter<T>,
+}
+
+impl<T: Value> Counter<T> {
+    /// Split the [Counter] into a handle modifying the value and a handle observing it.
+    pub fn split(self) -> (CounterWriter<T>, CounterReader<T>) {
+        let reader = self.reader();
+        (CounterWriter { counter: self }, reader)
+    }
+
+    /// Create a handle observing the [Counter] value without being able to modify it.
+    pub fn reader(&self) -> CounterReader<T> {
+        CounterReader {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Value> CounterReader<T> {
+    /// Fetch the current [Counter] value.
+    pub fn value(&self) -> T {
+        self.inner.value()
+    }
+
+    /// Fetch the `target` [Counter] value.
+    pub fn target(&self) -> T {
+        self.inner.target()
+    }
+
+    /// Fetch the version of the [Counter] value, incremented by every modification.
+    pub fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    /// Fetch the change of the value per second, or `None` if the rate is not tracked.
+    pub fn rate(&self) -> Option<f64> {
+        self.inner.rate()
+    }
+
+    /// Estimate the time until the value reaches the `target` at the current rate.
+    pub fn eta(&self) -> Option<Duration> {
+        self.inner.eta()
+    }
+
+    /// Fetch the value as a fraction of the `target`.
+    pub fn progress(&self) -> f64 {
+        self.inner.progress()
+    }
+
+    /// Fetch the number of handles able to modify the [Counter] value.
+    pub fn writer_count(&self) -> usize {
+        self.inner.writers.load(Ordering::Acquire)
+    }
+
+    /// Check whether the [Counter] is closed.
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+
+    /// Fetch the state of the gate, or `None` if the [Counter] has no [Watermarks](crate::Watermarks).
+    pub fn gate_state(&self) -> Option<GateState> {
+        self.inner.gate_state()
+    }
+
+    /// Create a future resolving once the [Counter] value is at least its `target`.
+    pub fn wait(&self) -> WaitFor<'_, T> {
+        WaitFor::new(&self.inner, Target::Shared)
+    }
+
+    /// Create a future resolving once the [Counter] value is at least `target`.
+    pub fn wait_for(&self, target: T) -> WaitFor<'_, T> {
+        WaitFor::new(&self.inner, Target::Fixed(target))
+    }
+
+    /// Create a `'static` future resolving once the [Counter] value is at least `target`.
+    pub fn wait_for_owned(&self, target: T) -> OwnedWaitFor<T> {
+        OwnedWaitFor::new(self.inner.clone(), Target::Fixed(target))
+    }
+
+    /// Block the current thread until the [Counter] value is at least `target`.
+    pub fn wait_blocking(&self, target: T) -> Result<T, Closed<T>> {
+        self.inner.wait_blocking(target)
+    }
+
+    /// Block the current thread until the [Counter] value is at least `target`
+    /// for at most `timeout`.
+    pub fn wait_blocking_timeout(
+        &self,
+        target: T,
+        timeout: Duration,
+    ) -> Result<T, WaitTimeoutError<T>> {
+        self.inner.wait_blocking_timeout(target, timeout)
+    }
+
+    /// Create a future resolving once the [Counter] value is at most `bound`.
+    pub fn wait_at_most(&self, bound: T) -> WaitAtMost<'_, T> {
+        WaitAtMost::new(&self.inner, bound)
+    }
+
+    /// Create a future resolving once the [Counter] value drops to 0 or below.
+    pub fn wait_zero(&self) -> WaitAtMost<'_, T> {
+        self.wait_at_most(T::ZERO)
+    }
+
+    /// Create a future resolving once `predicate` holds for the [Counter] value.
+    pub fn wait_until<F>(&self, predicate: F) -> WaitUntil<'_, F, T>
+    where
+        F: FnMut(T) -> bool,
+    {
+        WaitUntil::new(&self.inner, predicate)
+    }
+
+    /// Create a future resolving once the [Counter] value rises from below `threshold`
+    /// to at least `threshold`.
+    pub fn wait_rising(&self, threshold: T) -> WaitEdge<'_, T> {
+        WaitEdge::new(&self.inner, threshold, true)
+    }
+
+    /// Create a future resolving once the [Counter] value falls from at least `threshold`
+    /// to below `threshold`.
+    pub fn wait_falling(&self, threshold: T) -> WaitEdge<'_, T> {
+        WaitEdge::new(&self.inner, threshold, false)
+    }
+
+    /// Create a future resolving with the current version and value
+    /// as soon as the [Counter] value is modified after the version `since`.
+    pub fn changed(&self, since: u64) -> Changed<'_, T> {
+        Changed::new(&self.inner, since)
+    }
+
+    /// Create a future resolving once the gate is in `state`.
+    pub fn wait_gate(&self, state: GateState) -> WaitGate<'_, T> {
+        WaitGate::new(&self.inner, state)
+    }
+
+    /// Create a stream of [MilestoneReached](crate::MilestoneReached) events,
+    /// one for each of `thresholds` as soon as the [Counter] value reaches it.
+    pub fn milestones(&self, thresholds: impl IntoIterator<Item = T>) -> Milestones<T> {
+        Milestones::new(self.inner.clone(), thresholds)
+    }
+
+    /// Create a stream of the [Counter] values, starting with the current one.
+    #[cfg(feature = "futures")]
+    pub fn changes(&self) -> Changes<T> {
+        Changes::new(self.inner.clone())
+    }
+}
+
+impl<T: Value> IntoFuture for CounterReader<T> {
+    type Output = Result<T, Closed<T>>;
+    type IntoFuture = OwnedWaitFor<T>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        OwnedWaitFor::new(self.inner, Target::Shared)
+    }
+}
+
+impl<T: Value> CounterWriter<T> {
+    /// Fetch the current [Counter] value.
+    pub fn value(&self) -> T {
+        self.counter.value()
+    }
+
+    /// Create a handle observing the [Counter] value.
+    pub fn reader(&self) -> CounterReader<T> {
+        self.counter.reader()
+    }
+
+    /// Fetch the state of the gate, or `None` if the [Counter] has no [Watermarks](crate::Watermarks).
+    pub fn gate_state(&self) -> Option<GateState> {
+        self.counter.gate_state()
+    }
+
+    /// Create a future resolving once the gate is in `state`.
+    pub fn wait_gate(&self, state: GateState) -> WaitGate<'_, T> {
+        self.counter.wait_gate(state)
+    }
+
+    /// Create a future resolving once producers may proceed,
+    /// that is immediately unless the gate is [GateState::Paused].
+    pub fn wait_writable(&self) -> WaitGate<'_, T> {
+        self.counter.wait_writable()
+    }
+
+    /// Set the [Counter] value and wake all waiters.
+    pub fn set(&self, rhs: T) {
+        self.counter.set(rhs);
+    }
+
+    /// Replace the `target` of the [Counter], waking all waiters.
+    pub fn set_target(&self, target: T) {
+        self.counter.set_target(target);
+    }
+
+    /// Add `rhs` to the [Counter] value returning the new value,
+    /// or leave the value unchanged if the result overflows.
+    pub fn try_add(&self, rhs: T) -> Result<T, ArithmeticError> {
+        self.counter.try_add(rhs)
+    }
+
+    /// Subtract `rhs` from the [Counter] value returning the new value,
+    /// or leave the value unchanged if the result underflows.
+    pub fn try_sub(&self, rhs: T) -> Result<T, ArithmeticError> {
+        self.counter.try_sub(rhs)
+    }
+
+    /// Add `rhs` to the [Counter] value clamping at the maximum, returning the new value.
+    pub fn saturating_add(&self, rhs: T) -> T {
+        self.counter.saturating_add(rhs)
+    }
+
+    /// Subtract `rhs` from the [Counter] value clamping at the minimum, returning the new value.
+    pub fn saturating_sub(&self, rhs: T) -> T {
+        self.counter.saturating_sub(rhs)
+    }
+
+    /// Add `rhs` to the [Counter] value according to the [OverflowPolicy](crate::OverflowPolicy),
+    /// returning the previous value.
+    pub fn fetch_add(&self, rhs: T) -> T {
+        self.counter.fetch_add(rhs)
+    }
+
+    /// Subtract `rhs` from the [Counter] value according to the [OverflowPolicy](crate::OverflowPolicy),
+    /// returning the previous value.
+    pub fn fetch_sub(&self, rhs: T) -> T {
+        self.counter.fetch_sub(rhs)
+    }
+
+    /// Replace the [Counter] value with `value`, returning the previous value.
+    pub fn swap(&self, value: T) -> T {
+        self.counter.swap(value)
+    }
+
+    /// Replace the [Counter] value with `new` if it equals `current`.
+    pub fn compare_exchange(&self, current: T, new: T) -> Result<T, T> {
+        self.counter.compare_exchange(current, new)
+    }
+
+    /// Replace the [Counter] value with the result of `f` if any.
+    pub fn fetch_update<F>(&self, f: F) -> Result<T, T>
+    where
+        F: FnMut(T) -> Option<T>,
+    {
+        self.counter.fetch_update(f)
+    }
+
+    /// Increment the [Counter] value by 1 until the returned guard is dropped.
+    pub fn guard(&self) -> CounterGuard<T> {
+        self.counter.guard()
+    }
+
+    /// Increment the [Counter] value by `n` until the returned guard is dropped.
+    pub fn guard_n(&self, n: T) -> CounterGuard<T> {
+        self.counter.guard_n(n)
+    }
+
+    /// Close the [Counter], failing every future which is not satisfied yet with [Closed].
+    pub fn close(&self) {
+        self.counter.close();
+    }
+}
+
+impl<T: Value> AddAssign<T> for CounterWriter<T> {
+    fn add_assign(&mut self, rhs: T) {
+        self.counter += rhs;
+    }
+}
+
+impl<T: Value> SubAssign<T> for CounterWriter<T> {
+    fn sub_assign(&mut self, rhs: T) {
+        self.counter -= rhs;
+    }
+}
+
+impl<T: Value> Add<T> for CounterWriter<T> {
+    type Output = Self;
+
+    fn add(mut self, rhs: T) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<T: Value> Sub<T> for CounterWriter<T> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: T) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::{Closed, Counter, GateState, Watermarks};
+    use std::ops::Mul;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn reader_detects_no_producers_left() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let (writer, reader) = Counter::to(10).split();
+        assert_eq!(reader.writer_count(), 1);
+
+        // Several producers, each of them stops early.
+        for _ in 0u8..2 {
+            let mut producer = writer.clone();
+            tokio::spawn(async move {
+                time::sleep(counting_interval).await;
+                producer += 2;
+            });
+        }
+        assert_eq!(reader.writer_count(), 3);
+        drop(writer);
+
+        let r = time::timeout(counting_interval.mul(20), reader.wait_for(4)).await;
+        assert!(matches!(r, Ok(Ok(4))));
+
+        let r = time::timeout(counting_interval.mul(20), reader.clone()).await;
+        assert!(matches!(r, Ok(Err(Closed { value: 4 }))));
+        assert_eq!(reader.writer_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn writer_pauses_on_gate() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let (mut writer, reader) = Counter::to(0)
+            .with_watermarks(Watermarks { low: 1, high: 2 })
+            .split();
+
+        writer += 2;
+        assert_eq!(writer.gate_state(), Some(GateState::Paused));
+        assert_eq!(reader.gate_state(), Some(GateState::Paused));
+
+        // The consumer drains the counter below the low watermark.
+        let mut consumer = writer.clone();
+        tokio::spawn(async move {
+            time::sleep(counting_interval).await;
+            consumer -= 2;
+        });
+
+        let r = time::timeout(counting_interval.mul(20), writer.wait_writable()).await;
+        assert!(matches!(r, Ok(Ok(0))));
+        let r = time::timeout(counting_interval, reader.wait_gate(GateState::Open)).await;
+        assert!(matches!(r, Ok(Ok(0))));
+    }
+}
@@ -0,0 +1,8 @@
+//! Synchronization primitives, replaced by their [loom](https://docs.rs/loom) models
+//! when built with `--cfg loom`.
+
+#[cfg(loom)]
+pub(crate) use loom::sync::{atomic, Mutex};
+
+#[cfg(not(loom))]
+pub(crate) use std::sync::{atomic, Mutex};
@@ -0,0 +1,247 @@
+use crate::sync::atomic::{
+    AtomicI16, AtomicI32, AtomicI8, AtomicIsize, AtomicU16, AtomicU32, AtomicU8, AtomicUsize,
+    Ordering,
+};
+#[cfg(target_has_atomic = "64")]
+use crate::sync::atomic::{AtomicI64, AtomicU64};
+use std::fmt;
+use std::ops::{Add, Sub};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Atomic integer storing the value of a [Counter](crate::Counter).
+///
+/// This trait is sealed and implemented for the atomic counterparts of the [Value] types.
+pub trait Atomic: private::Sealed + fmt::Debug + Send + Sync {
+    /// Integer type stored in the atomic.
+    type Value;
+
+    /// Create an atomic holding `value`.
+    fn new(value: Self::Value) -> Self;
+
+    /// Load the stored value.
+    fn load(&self, order: Ordering) -> Self::Value;
+
+    /// Store `value`.
+    fn store(&self, value: Self::Value, order: Ordering);
+
+    /// Replace the stored value with `value`, returning the previous value.
+    fn swap(&self, value: Self::Value, order: Ordering) -> Self::Value;
+
+    /// Replace the stored value with `new` if it equals `current`, returning the previous value.
+    fn compare_exchange(
+        &self,
+        current: Self::Value,
+        new: Self::Value,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Self::Value, Self::Value>;
+
+    /// Add `value` wrapping around on overflow, returning the previous value.
+    fn fetch_add(&self, value: Self::Value, order: Ordering) -> Self::Value;
+
+    /// Subtract `value` wrapping around on overflow, returning the previous value.
+    fn fetch_sub(&self, value: Self::Value, order: Ordering) -> Self::Value;
+
+    /// Replace the stored value with the result of `f` if any, returning the previous value.
+    fn fetch_update<F>(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        f: F,
+    ) -> Result<Self::Value, Self::Value>
+    where
+        F: FnMut(Self::Value) -> Option<Self::Value>;
+}
+
+/// Integer type which can be the value of a [Counter](crate::Counter).
+///
+/// This trait is sealed and implemented for all primitive integers up to 64 bits
+/// having an atomic counterpart on the target platform.
+pub trait Value:
+    private::Sealed
+    + Copy
+    + Ord
+    + fmt::Debug
+    + fmt::Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Send
+    + Sync
+    + Unpin
+    + 'static
+{
+    /// Atomic counterpart of the integer type.
+    type Atomic: Atomic<Value = Self>;
+
+    /// Zero of the integer type.
+    const ZERO: Self;
+
+    /// One of the integer type.
+    const ONE: Self;
+
+    /// Add `rhs` returning `None` on overflow.
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    /// Subtract `rhs` returning `None` on overflow.
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+    /// Add `rhs` clamping at the numeric bounds.
+    fn saturating_add(self, rhs: Self) -> Self;
+
+    /// Subtract `rhs` clamping at the numeric bounds.
+    fn saturating_sub(self, rhs: Self) -> Self;
+
+    /// Add `rhs` wrapping around the numeric bounds.
+    fn wrapping_add(self, rhs: Self) -> Self;
+
+    /// Subtract `rhs` wrapping around the numeric bounds.
+    fn wrapping_sub(self, rhs: Self) -> Self;
+
+    /// Convert to a floating point number, possibly losing precision.
+    fn as_f64(self) -> f64;
+}
+
+macro_rules! impl_value {
+    ($($(#[$meta:meta])* $value:ty => $atomic:ty),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            impl private::Sealed for $value {}
+
+            $(#[$meta])*
+            impl private::Sealed for $atomic {}
+
+            $(#[$meta])*
+            impl Atomic for $atomic {
+                type Value = $value;
+
+                fn new(value: $value) -> Self {
+                    <$atomic>::new(value)
+                }
+
+                fn load(&self, order: Ordering) -> $value {
+                    <$atomic>::load(self, order)
+                }
+
+                fn store(&self, value: $value, order: Ordering) {
+                    <$atomic>::store(self, value, order)
+                }
+
+                fn swap(&self, value: $value, order: Ordering) -> $value {
+                    <$atomic>::swap(self, value, order)
+                }
+
+                fn compare_exchange(
+                    &self,
+                    current: $value,
+                    new: $value,
+                    success: Ordering,
+                    failure: Ordering,
+                ) -> Result<$value, $value> {
+                    <$atomic>::compare_exchange(self, current, new, success, failure)
+                }
+
+                fn fetch_add(&self, value: $value, order: Ordering) -> $value {
+                    <$atomic>::fetch_add(self, value, order)
+                }
+
+                fn fetch_sub(&self, value: $value, order: Ordering) -> $value {
+                    <$atomic>::fetch_sub(self, value, order)
+                }
+
+                fn fetch_update<F>(
+                    &self,
+                    set_order: Ordering,
+                    fetch_order: Ordering,
+                    f: F,
+                ) -> Result<$value, $value>
+                where
+                    F: FnMut($value) -> Option<$value>,
+                {
+                    <$atomic>::fetch_update(self, set_order, fetch_order, f)
+                }
+            }
+
+            $(#[$meta])*
+            impl Value for $value {
+                type Atomic = $atomic;
+
+                const ZERO: Self = 0;
+
+                const ONE: Self = 1;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$value>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$value>::checked_sub(self, rhs)
+                }
+
+                fn saturating_add(self, rhs: Self) -> Self {
+                    <$value>::saturating_add(self, rhs)
+                }
+
+                fn saturating_sub(self, rhs: Self) -> Self {
+                    <$value>::saturating_sub(self, rhs)
+                }
+
+                fn wrapping_add(self, rhs: Self) -> Self {
+                    <$value>::wrapping_add(self, rhs)
+                }
+
+                fn wrapping_sub(self, rhs: Self) -> Self {
+                    <$value>::wrapping_sub(self, rhs)
+                }
+
+                fn as_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_value! {
+    u8 => AtomicU8,
+    u16 => AtomicU16,
+    u32 => AtomicU32,
+    #[cfg(target_has_atomic = "64")]
+    u64 => AtomicU64,
+    usize => AtomicUsize,
+    i8 => AtomicI8,
+    i16 => AtomicI16,
+    i32 => AtomicI32,
+    #[cfg(target_has_atomic = "64")]
+    i64 => AtomicI64,
+    isize => AtomicIsize,
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::{Counter, OverflowPolicy};
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn counter_of_any_width() {
+        let counting_interval = Duration::from_millis(10);
+
+        let narrow = Counter::<u32>::with_target(u32::MAX).with_policy(OverflowPolicy::Saturate);
+        let wide = Counter::<u64>::with_value(u64::from(u32::MAX), u64::MAX);
+
+        let mut count = narrow.clone();
+        count += u32::MAX - 1;
+        count += 5;
+        assert_eq!(narrow.value(), u32::MAX);
+
+        wide.set(u64::MAX);
+        let r = time::timeout(counting_interval, wide).await;
+        assert!(matches!(r, Ok(Ok(u64::MAX))));
+
+        let r = time::timeout(counting_interval, narrow).await;
+        assert!(matches!(r, Ok(Ok(u32::MAX))));
+    }
+}
@@ -0,0 +1,305 @@
+use crate::wait::Waiter;
+use crate::{Closed, Counter, Inner, Value};
+#[cfg(feature = "futures")]
+use futures_core::FusedFuture;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Read-only value aggregated from several [Counter]s.
+///
+/// The value is recomputed from the sources on every read,
+/// and the futures awaiting the view are woken whenever any source changes.
+/// A view does not keep its sources open, it is closed once all of them are closed.
+#[derive(Clone)]
+pub struct CounterView<T: Value = usize> {
+    sources: Vec<Arc<Inner<T>>>,
+    aggregate: Aggregate<T>,
+}
+
+/// Combination of the source values of a [CounterView].
+#[derive(Clone)]
+enum Aggregate<T> {
+    Sum,
+    Min,
+    Max,
+    Fold(T, Arc<dyn Fn(T, T) -> T + Send + Sync>),
+}
+
+impl<T: Value> CounterView<T> {
+    /// Create a view on the sum of `counters`, clamping at the numeric bounds.
+    pub fn sum<'a, I>(counters: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Counter<T>>,
+    {
+        Self::new(counters, Aggregate::Sum)
+    }
+
+    /// Create a view on the minimum of `counters`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `counters` is empty.
+    pub fn min<'a, I>(counters: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Counter<T>>,
+    {
+        let view = Self::new(counters, Aggregate::Min);
+        assert!(!view.sources.is_empty(), "Minimum of no counters");
+        view
+    }
+
+    /// Create a view on the maximum of `counters`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `counters` is empty.
+    pub fn max<'a, I>(counters: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Counter<T>>,
+    {
+        let view = Self::new(counters, Aggregate::Max);
+        assert!(!view.sources.is_empty(), "Maximum of no counters");
+        view
+    }
+
+    /// Create a view folding the values of `counters` with `f` starting from `init`.
+    pub fn fold<'a, I, F>(counters: I, init: T, f: F) -> Self
+    where
+        I: IntoIterator<Item = &'a Counter<T>>,
+        F: Fn(T, T) -> T + Send + Sync + 'static,
+    {
+        Self::new(counters, Aggregate::Fold(init, Arc::new(f)))
+    }
+
+    fn new<'a, I>(counters: I, aggregate: Aggregate<T>) -> Self
+    where
+        I: IntoIterator<Item = &'a Counter<T>>,
+    {
+        Self {
+            sources: counters
+                .into_iter()
+                .map(|counter| counter.inner.clone())
+                .collect(),
+            aggregate,
+        }
+    }
+
+    /// Fetch the current aggregated value.
+    pub fn value(&self) -> T {
+        let values = self.sources.iter().map(|inner| inner.value());
+        match &self.aggregate {
+            Aggregate::Sum => values.fold(T::ZERO, T::saturating_add),
+            Aggregate::Min => values.min().expect("View has sources"),
+            Aggregate::Max => values.max().expect("View has sources"),
+            Aggregate::Fold(init, f) => values.fold(*init, |acc, value| f(acc, value)),
+        }
+    }
+
+    /// Create a future resolving once the aggregated value is at least `target`.
+    pub fn wait_for(&self, target: T) -> ViewWaitFor<'_, T> {
+        ViewWaitFor {
+            waiter: ViewWaiter::new(self),
+            target,
+        }
+    }
+
+    /// Create a future resolving once `predicate` holds for the aggregated value.
+    ///
+    /// `predicate` is re-evaluated every time any source changes.
+    pub fn wait_until<F>(&self, predicate: F) -> ViewWaitUntil<'_, F, T>
+    where
+        F: FnMut(T) -> bool,
+    {
+        ViewWaitUntil {
+            waiter: ViewWaiter::new(self),
+            predicate,
+        }
+    }
+}
+
+impl<T: Value> fmt::Debug for CounterView<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let aggregate = match self.aggregate {
+            Aggregate::Sum => "Sum",
+            Aggregate::Min => "Min",
+            Aggregate::Max => "Max",
+            Aggregate::Fold(..) => "Fold",
+        };
+        f.debug_struct("CounterView")
+            .field("sources", &self.sources)
+            .field("aggregate", &aggregate)
+            .finish()
+    }
+}
+
+/// Registration of a single future in the waiters of every source of a [CounterView].
+#[derive(Debug)]
+struct ViewWaiter<'a, T: Value> {
+    view: &'a CounterView<T>,
+    waiters: Vec<Waiter<T, &'a Inner<T>>>,
+    done: bool,
+}
+
+impl<'a, T: Value> ViewWaiter<'a, T> {
+    fn new(view: &'a CounterView<T>) -> Self {
+        Self {
+            view,
+            waiters: view
+                .sources
+                .iter()
+                .map(|inner| Waiter::new(&**inner))
+                .collect(),
+            done: false,
+        }
+    }
+
+    /// The value is checked again once registered in all sources:
+    /// a modification racing with the registration is either observed or wakes the future.
+    fn poll_until(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut ready: impl FnMut(T) -> bool,
+    ) -> Poll<Result<T, Closed<T>>> {
+        let value = self.view.value();
+        if ready(value) {
+            self.done = true;
+            return Poll::Ready(Ok(value));
+        }
+
+        let mut closed = true;
+        for waiter in &mut self.waiters {
+            closed &= waiter.register(cx);
+        }
+
+        let value = self.view.value();
+        if ready(value) {
+            self.done = true;
+            Poll::Ready(Ok(value))
+        } else if closed {
+            self.done = true;
+            Poll::Ready(Err(Closed { value }))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Future resolving once the value of a borrowed [CounterView] is at least `target`.
+///
+/// Created by [CounterView::wait_for].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ViewWaitFor<'a, T: Value = usize> {
+    waiter: ViewWaiter<'a, T>,
+    target: T,
+}
+
+impl<T: Value> Future for ViewWaitFor<'_, T> {
+    type Output = Result<T, Closed<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let target = this.target;
+        this.waiter.poll_until(cx, |value| value >= target)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Value> FusedFuture for ViewWaitFor<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.waiter.done
+    }
+}
+
+/// Future resolving once the value of a borrowed [CounterView] satisfies a predicate.
+///
+/// Created by [CounterView::wait_until].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ViewWaitUntil<'a, F, T: Value = usize> {
+    waiter: ViewWaiter<'a, T>,
+    predicate: F,
+}
+
+// The predicate is never pinned, it is only called through a mutable reference.
+impl<F, T: Value> Unpin for ViewWaitUntil<'_, F, T> {}
+
+impl<F, T: Value> Future for ViewWaitUntil<'_, F, T>
+where
+    F: FnMut(T) -> bool,
+{
+    type Output = Result<T, Closed<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.waiter.poll_until(cx, &mut this.predicate)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<F, T: Value> FusedFuture for ViewWaitUntil<'_, F, T>
+where
+    F: FnMut(T) -> bool,
+{
+    fn is_terminated(&self) -> bool {
+        self.waiter.done
+    }
+}
+
+impl<F, T: Value> fmt::Debug for ViewWaitUntil<'_, F, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ViewWaitUntil")
+            .field("waiter", &self.waiter)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::{Closed, Counter, CounterView};
+    use std::ops::Mul;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn view_follows_shards() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let shards = [Counter::to(5), Counter::to(5), Counter::to(5)];
+        let total = CounterView::sum(&shards);
+        let slowest = CounterView::min(&shards);
+        let fastest = CounterView::max(&shards);
+        let squares = CounterView::fold(&shards, 0, |acc, v| acc + v * v);
+
+        // Shards progress at different paces.
+        let counts = shards.clone();
+        tokio::spawn(async move {
+            for i in 1..=5 {
+                time::sleep(counting_interval).await;
+                for (pace, count) in counts.iter().enumerate() {
+                    count.set(i * (pace + 1));
+                }
+            }
+        });
+
+        let (total, slowest, fastest) = tokio::join!(
+            time::timeout(counting_interval.mul(20), total.wait_for(30)),
+            time::timeout(counting_interval.mul(20), slowest.wait_for(5)),
+            time::timeout(counting_interval.mul(20), fastest.wait_until(|v| v >= 9)),
+        );
+        assert!(matches!(total, Ok(Ok(30))));
+        assert!(matches!(slowest, Ok(Ok(5))));
+        assert!(matches!(fastest, Ok(Ok(9))));
+        assert_eq!(squares.value(), 25 + 100 + 225);
+
+        // The view is closed once all sources are closed.
+        let total = CounterView::sum(&shards);
+        drop(shards);
+        let r = time::timeout(counting_interval, total.wait_for(100)).await;
+        assert!(matches!(r, Ok(Err(Closed { value: 30 }))));
+    }
+}
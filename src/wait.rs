@@ -0,0 +1,655 @@
+use crate::{Closed, GateState, Inner, MilestoneReached, Value};
+#[cfg(feature = "futures")]
+use futures_core::FusedFuture;
+use std::fmt;
+use std::future::Future;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Registration of a single future in the waiters of a [Counter](crate::Counter).
+///
+/// The slot is released when the registration is dropped,
+/// so that abandoned futures do not accumulate in the registry.
+#[derive(Debug)]
+pub(crate) struct Waiter<T: Value, S: Deref<Target = Inner<T>>> {
+    inner: S,
+    key: Option<usize>,
+    /// Whether the future has resolved.
+    done: bool,
+}
+
+impl<T: Value, S: Deref<Target = Inner<T>>> Waiter<T, S> {
+    pub(crate) fn new(inner: S) -> Self {
+        Self {
+            inner,
+            key: None,
+            done: false,
+        }
+    }
+
+    pub(crate) fn poll_until(
+        &mut self,
+        cx: &mut Context<'_>,
+        ready: impl FnMut(T) -> bool,
+    ) -> Poll<Result<T, Closed<T>>> {
+        let poll = self.inner.poll_until(&mut self.key, cx, ready);
+        self.done = poll.is_ready();
+        poll
+    }
+
+    pub(crate) fn poll_target(
+        &mut self,
+        cx: &mut Context<'_>,
+        target: Target<T>,
+    ) -> Poll<Result<T, Closed<T>>> {
+        let inner = &*self.inner;
+        let poll = inner.poll_until(&mut self.key, cx, |value| value >= target.get(inner));
+        self.done = poll.is_ready();
+        poll
+    }
+
+    pub(crate) fn poll_changed(
+        &mut self,
+        cx: &mut Context<'_>,
+        since: Option<u64>,
+    ) -> Poll<Result<(u64, T), Closed<T>>> {
+        let poll = self.inner.poll_changed(&mut self.key, cx, since);
+        self.done = poll.is_ready();
+        poll
+    }
+
+    pub(crate) fn poll_take(&mut self, cx: &mut Context<'_>, n: T) -> Poll<Result<T, Closed<T>>> {
+        let poll = self.inner.poll_take(&mut self.key, cx, n);
+        self.done = poll.is_ready();
+        poll
+    }
+
+    pub(crate) fn poll_milestone(
+        &mut self,
+        cx: &mut Context<'_>,
+        milestones: usize,
+    ) -> Poll<Option<MilestoneReached<T>>> {
+        // Once the events end, polling again keeps returning `None`.
+        if self.done {
+            return Poll::Ready(None);
+        }
+        let poll = self.inner.poll_milestone(&mut self.key, cx, milestones);
+        self.done = matches!(poll, Poll::Ready(None));
+        poll
+    }
+
+    pub(crate) fn remove_milestones(&mut self, milestones: usize) {
+        self.inner.remove_milestones(milestones);
+    }
+
+    pub(crate) fn poll_edge(
+        &mut self,
+        cx: &mut Context<'_>,
+        edge: usize,
+    ) -> Poll<Result<T, Closed<T>>> {
+        let poll = self.inner.poll_edge(&mut self.key, cx, edge);
+        self.done = poll.is_ready();
+        poll
+    }
+
+    pub(crate) fn remove_edge(&mut self, edge: usize) {
+        self.inner.remove_edge(edge);
+    }
+
+    pub(crate) fn poll_gate(
+        &mut self,
+        cx: &mut Context<'_>,
+        state: GateState,
+    ) -> Poll<Result<T, Closed<T>>> {
+        let poll = self.inner.poll_gate(&mut self.key, cx, state);
+        self.done = poll.is_ready();
+        poll
+    }
+
+    /// Leave the queue of the takers if still in it.
+    pub(crate) fn cancel_take(&mut self) {
+        self.inner.cancel_take(self.key);
+    }
+
+    /// Register the waker of `cx` unconditionally, returns whether the counter is closed.
+    pub(crate) fn register(&mut self, cx: &mut Context<'_>) -> bool {
+        self.inner.register(&mut self.key, cx)
+    }
+
+    #[cfg(feature = "futures")]
+    pub(crate) fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+impl<T: Value, S: Deref<Target = Inner<T>>> Drop for Waiter<T, S> {
+    fn drop(&mut self) {
+        self.inner.deregister(self.key.take());
+    }
+}
+
+/// Target awaited by [WaitFor] and [OwnedWaitFor].
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Target<T> {
+    /// Target given explicitly on creation of the future.
+    Fixed(T),
+    /// Target of the counter, which may change while the future is pending.
+    Shared,
+}
+
+impl<T: Value> Target<T> {
+    fn get(self, inner: &Inner<T>) -> T {
+        match self {
+            Self::Fixed(target) => target,
+            Self::Shared => inner.target(),
+        }
+    }
+}
+
+/// Future resolving once the value of a borrowed [Counter](crate::Counter) is at least `target`.
+///
+/// Created by [Counter::wait_for](crate::Counter::wait_for) and [Counter::wait](crate::Counter::wait).
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitFor<'a, T: Value = usize> {
+    waiter: Waiter<T, &'a Inner<T>>,
+    target: Target<T>,
+}
+
+impl<'a, T: Value> WaitFor<'a, T> {
+    pub(crate) fn new(inner: &'a Inner<T>, target: Target<T>) -> Self {
+        Self {
+            waiter: Waiter::new(inner),
+            target,
+        }
+    }
+}
+
+impl<T: Value> Future for WaitFor<'_, T> {
+    type Output = Result<T, Closed<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.waiter.poll_target(cx, this.target)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Value> FusedFuture for WaitFor<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.waiter.is_done()
+    }
+}
+
+/// Future resolving once the value of a [Counter](crate::Counter) is at least `target`.
+///
+/// Unlike [WaitFor], it keeps the counter state alive and thus is `'static`.
+/// Created by [Counter::wait_for_owned](crate::Counter::wait_for_owned) or by awaiting a [Counter](crate::Counter).
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct OwnedWaitFor<T: Value = usize> {
+    waiter: Waiter<T, Arc<Inner<T>>>,
+    target: Target<T>,
+}
+
+impl<T: Value> OwnedWaitFor<T> {
+    pub(crate) fn new(inner: Arc<Inner<T>>, target: Target<T>) -> Self {
+        Self {
+            waiter: Waiter::new(inner),
+            target,
+        }
+    }
+}
+
+impl<T: Value> Future for OwnedWaitFor<T> {
+    type Output = Result<T, Closed<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.waiter.poll_target(cx, this.target)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Value> FusedFuture for OwnedWaitFor<T> {
+    fn is_terminated(&self) -> bool {
+        self.waiter.is_done()
+    }
+}
+
+/// Future resolving once the value of a borrowed [Counter](crate::Counter) is at most `bound`.
+///
+/// Created by [Counter::wait_at_most](crate::Counter::wait_at_most) and [Counter::wait_zero](crate::Counter::wait_zero).
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitAtMost<'a, T: Value = usize> {
+    waiter: Waiter<T, &'a Inner<T>>,
+    bound: T,
+}
+
+impl<'a, T: Value> WaitAtMost<'a, T> {
+    pub(crate) fn new(inner: &'a Inner<T>, bound: T) -> Self {
+        Self {
+            waiter: Waiter::new(inner),
+            bound,
+        }
+    }
+}
+
+impl<T: Value> Future for WaitAtMost<'_, T> {
+    type Output = Result<T, Closed<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let bound = this.bound;
+        this.waiter.poll_until(cx, |value| value <= bound)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Value> FusedFuture for WaitAtMost<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.waiter.is_done()
+    }
+}
+
+/// Future resolving with the version and value of a borrowed [Counter](crate::Counter)
+/// once the value is modified after a known version.
+///
+/// Created by [Counter::changed](crate::Counter::changed).
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Changed<'a, T: Value = usize> {
+    waiter: Waiter<T, &'a Inner<T>>,
+    since: u64,
+}
+
+impl<'a, T: Value> Changed<'a, T> {
+    pub(crate) fn new(inner: &'a Inner<T>, since: u64) -> Self {
+        Self {
+            waiter: Waiter::new(inner),
+            since,
+        }
+    }
+}
+
+impl<T: Value> Future for Changed<'_, T> {
+    type Output = Result<(u64, T), Closed<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.waiter.poll_changed(cx, Some(this.since))
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Value> FusedFuture for Changed<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.waiter.is_done()
+    }
+}
+
+/// Future taking an amount from the value of a borrowed [Counter](crate::Counter)
+/// once it is available, resolving with the value left.
+///
+/// Created by [Counter::take](crate::Counter::take).
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Take<'a, T: Value = usize> {
+    waiter: Waiter<T, &'a Inner<T>>,
+    n: T,
+}
+
+impl<'a, T: Value> Take<'a, T> {
+    pub(crate) fn new(inner: &'a Inner<T>, n: T) -> Self {
+        Self {
+            waiter: Waiter::new(inner),
+            n,
+        }
+    }
+}
+
+impl<T: Value> Future for Take<'_, T> {
+    type Output = Result<T, Closed<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.waiter.poll_take(cx, this.n)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Value> FusedFuture for Take<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.waiter.is_done()
+    }
+}
+
+impl<T: Value> Drop for Take<'_, T> {
+    fn drop(&mut self) {
+        self.waiter.cancel_take();
+    }
+}
+
+/// Future resolving once the value of a borrowed [Counter](crate::Counter) satisfies a predicate.
+///
+/// The predicate is re-evaluated on every change of the value.
+/// Created by [Counter::wait_until](crate::Counter::wait_until).
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitUntil<'a, F, T: Value = usize> {
+    waiter: Waiter<T, &'a Inner<T>>,
+    predicate: F,
+}
+
+impl<'a, F, T: Value> WaitUntil<'a, F, T>
+where
+    F: FnMut(T) -> bool,
+{
+    pub(crate) fn new(inner: &'a Inner<T>, predicate: F) -> Self {
+        Self {
+            waiter: Waiter::new(inner),
+            predicate,
+        }
+    }
+}
+
+// The predicate is never pinned, it is only called through a mutable reference.
+impl<F, T: Value> Unpin for WaitUntil<'_, F, T> {}
+
+impl<F, T: Value> Future for WaitUntil<'_, F, T>
+where
+    F: FnMut(T) -> bool,
+{
+    type Output = Result<T, Closed<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.waiter.poll_until(cx, &mut this.predicate)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<F, T: Value> FusedFuture for WaitUntil<'_, F, T>
+where
+    F: FnMut(T) -> bool,
+{
+    fn is_terminated(&self) -> bool {
+        self.waiter.is_done()
+    }
+}
+
+impl<F, T: Value> fmt::Debug for WaitUntil<'_, F, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitUntil")
+            .field("waiter", &self.waiter)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::{Closed, Counter, SignedCounter};
+    use log::debug;
+    use std::ops::Mul;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn counter_serves_many_targets() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::to(10);
+        let mut count = counter.clone();
+
+        // Spawn a task to update the counter.
+        tokio::spawn(async move {
+            for i in 0u8..4 {
+                time::sleep(counting_interval).await;
+                debug!("Tick {i}");
+                count += 5;
+            }
+        });
+
+        // Wait for several targets on the same counter.
+        let (low, high) = tokio::join!(
+            time::timeout(counting_interval.mul(20), counter.wait_for(5)),
+            time::timeout(counting_interval.mul(20), counter.wait_for(20)),
+        );
+        assert!(matches!(low, Ok(Ok(t)) if t >= 5));
+        assert!(matches!(high, Ok(Ok(t)) if t == 20));
+
+        debug!("All targets are reached!");
+    }
+
+    #[tokio::test]
+    async fn owned_wait_for_is_static() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::to(10);
+        let waiter = tokio::spawn(time::timeout(
+            counting_interval.mul(20),
+            counter.wait_for_owned(3),
+        ));
+
+        time::sleep(counting_interval).await;
+        counter.set(3);
+
+        let r = waiter.await.expect("Waiting task must not panic");
+        assert!(matches!(r, Ok(Ok(3))));
+    }
+
+    #[tokio::test]
+    async fn wait_until_predicate_holds() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::new(7, 10);
+        let mut count = counter.clone();
+
+        // Spawn a task to update the counter.
+        tokio::spawn(async move {
+            for i in 0u8..7 {
+                time::sleep(counting_interval).await;
+                debug!("Tick {i}");
+                count -= 1;
+            }
+        });
+
+        let (even, drained) = tokio::join!(
+            time::timeout(
+                counting_interval.mul(20),
+                counter.wait_until(|v| v % 2 == 0)
+            ),
+            time::timeout(counting_interval.mul(20), counter.wait_until(|v| v == 0)),
+        );
+        assert!(matches!(even, Ok(Ok(6))));
+        assert!(matches!(drained, Ok(Ok(0))));
+    }
+
+    #[tokio::test]
+    async fn predicate_may_use_counter() {
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::to(10);
+        let count = counter.clone();
+
+        tokio::spawn(async move {
+            time::sleep(counting_interval).await;
+            count.close();
+        });
+
+        // The predicate calls back into the counter without deadlocking.
+        let r = time::timeout(
+            counting_interval.mul(20),
+            counter.wait_until(|v| v > 3 || counter.is_closed()),
+        )
+        .await;
+        assert!(matches!(r, Ok(Ok(0))));
+
+        // A panicking predicate leaves the counter usable.
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            // The first check passes, the one following the registration panics.
+            let mut checks = 0;
+            let wait = counter.wait_until(|_| {
+                checks += 1;
+                assert!(checks < 2, "Predicate failed");
+                false
+            });
+            futures::executor::block_on(wait)
+        }));
+        assert!(panicked.is_err());
+        counter.set(5);
+        assert_eq!(counter.value(), 5);
+    }
+
+    #[tokio::test]
+    async fn wait_zero_drains() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::to(10);
+        let mut count = counter.clone();
+
+        // Spawn a task to put work in flight and complete it.
+        tokio::spawn(async move {
+            count += 4;
+            for i in 0u8..4 {
+                time::sleep(counting_interval).await;
+                debug!("Tick {i}");
+                count -= 1;
+            }
+        });
+
+        time::sleep(counting_interval.div_f32(2.0)).await;
+
+        let (half, drained) = tokio::join!(
+            time::timeout(counting_interval.mul(20), counter.wait_at_most(2)),
+            time::timeout(counting_interval.mul(20), counter.wait_zero()),
+        );
+        assert!(matches!(half, Ok(Ok(2))));
+        assert!(matches!(drained, Ok(Ok(0))));
+    }
+
+    #[tokio::test]
+    async fn set_target_wakes_waiters() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::new(5, 10);
+        let waiter = tokio::spawn(time::timeout(counting_interval.mul(20), counter.clone()));
+
+        // The batch turns out to be smaller than expected.
+        time::sleep(counting_interval).await;
+        counter.set_target(5);
+
+        let r = waiter.await.expect("Waiting task must not panic");
+        assert!(matches!(r, Ok(Ok(5))));
+
+        // A fixed target is not affected by the new goal.
+        counter.set_target(20);
+        let (fixed, shared) = tokio::join!(
+            time::timeout(counting_interval, counter.wait_for(5)),
+            time::timeout(counting_interval, counter.wait()),
+        );
+        assert!(matches!(fixed, Ok(Ok(5))));
+        assert!(shared.is_err());
+    }
+
+    #[tokio::test]
+    async fn changed_reacts_to_every_change() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::to(10);
+        let mut count = counter.clone();
+        let version = counter.version();
+
+        // Setting the same value is a modification as well.
+        tokio::spawn(async move {
+            time::sleep(counting_interval).await;
+            count.set(0);
+            time::sleep(counting_interval).await;
+            count += 3;
+        });
+
+        let r = time::timeout(counting_interval.mul(20), counter.changed(version)).await;
+        let Ok(Ok((version, value))) = r else {
+            panic!("Change must be observed");
+        };
+        assert_eq!(value, 0);
+
+        let r = time::timeout(counting_interval.mul(20), counter.changed(version)).await;
+        assert!(matches!(r, Ok(Ok((v, 3))) if v > version));
+
+        // An outdated version resolves right away.
+        let r = time::timeout(counting_interval, counter.changed(0)).await;
+        assert!(matches!(r, Ok(Ok((_, 3)))));
+    }
+
+    #[tokio::test]
+    async fn takers_are_served_in_order() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let credits = Counter::to(0);
+        let mut producer = credits.clone();
+
+        let mut large = Box::pin(credits.take(5));
+        let mut small = Box::pin(credits.take(1));
+        assert!(futures::poll!(large.as_mut()).is_pending());
+        assert!(futures::poll!(small.as_mut()).is_pending());
+
+        // The small taker queues behind the large one.
+        producer += 1;
+        assert!(futures::poll!(small.as_mut()).is_pending());
+        assert_eq!(credits.try_take(1), None);
+
+        tokio::spawn(async move {
+            time::sleep(counting_interval).await;
+            producer += 5;
+        });
+
+        let (large, small) = tokio::join!(
+            time::timeout(counting_interval.mul(20), large),
+            time::timeout(counting_interval.mul(20), small),
+        );
+        assert!(matches!(large, Ok(Ok(1))));
+        assert!(matches!(small, Ok(Ok(0))));
+
+        credits.set(3);
+        assert_eq!(credits.try_take(2), Some(1));
+        assert_eq!(credits.try_take(2), None);
+
+        // Negative amounts are rejected instead of overflowing.
+        let signed = SignedCounter::with_value(isize::MAX, 0);
+        assert_eq!(signed.try_take(-1), None);
+        assert_eq!(signed.try_take(isize::MAX), Some(0));
+    }
+
+    #[tokio::test]
+    async fn close_fails_queued_takers() {
+        let counting_interval = Duration::from_millis(10);
+
+        let credits = Counter::to(0);
+        let mut first = Box::pin(credits.take(2));
+        let mut second = Box::pin(credits.take(1));
+        assert!(futures::poll!(first.as_mut()).is_pending());
+        assert!(futures::poll!(second.as_mut()).is_pending());
+
+        // The first taker is never polled again.
+        credits.close();
+        let r = time::timeout(counting_interval, second).await;
+        assert!(matches!(r, Ok(Err(Closed { value: 0 }))));
+    }
+}
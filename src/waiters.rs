@@ -0,0 +1,198 @@
+use crate::edge::Edge;
+use crate::milestones::{MilestoneReached, Tracker};
+use crate::watermarks::{Gate, GateState};
+use crate::Value;
+use std::collections::VecDeque;
+use std::task::Waker;
+
+/// Registry of wakers belonging to the futures pending on a [Counter](crate::Counter).
+///
+/// Every pending future owns a slot identified by a key, so that re-polling a future
+/// replaces its own waker instead of the waker of another future.
+///
+/// The registry also counts modifications of the value, all of which notify it,
+/// records whether the counter is closed, queues the futures taking from the value
+/// and tracks the milestones, edges and watermarks crossed by the modifications.
+#[derive(Debug)]
+pub(crate) struct Waiters<T> {
+    slots: Vec<Option<Waker>>,
+    free: Vec<usize>,
+    version: u64,
+    closed: bool,
+    /// Keys of the pending takers in arrival order.
+    takers: VecDeque<usize>,
+    milestones: Vec<Option<Tracker<T>>>,
+    edges: Vec<Option<Edge<T>>>,
+    gate: Option<Gate<T>>,
+}
+
+impl<T> Default for Waiters<T> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            version: 0,
+            closed: false,
+            takers: VecDeque::new(),
+            milestones: Vec::new(),
+            edges: Vec::new(),
+            gate: None,
+        }
+    }
+}
+
+impl<T: Value> Waiters<T> {
+    /// Store `waker` in the slot identified by `key`, allocating a new slot if `key` is empty.
+    pub(crate) fn register(&mut self, key: &mut Option<usize>, waker: &Waker) {
+        match *key {
+            Some(k) => match &mut self.slots[k] {
+                Some(current) if current.will_wake(waker) => {}
+                slot => *slot = Some(waker.clone()),
+            },
+            None => {
+                let k = match self.free.pop() {
+                    Some(k) => {
+                        self.slots[k] = Some(waker.clone());
+                        k
+                    }
+                    None => {
+                        self.slots.push(Some(waker.clone()));
+                        self.slots.len() - 1
+                    }
+                };
+                *key = Some(k);
+            }
+        }
+    }
+
+    /// Release the slot identified by `key`.
+    pub(crate) fn remove(&mut self, key: usize) {
+        self.slots[key] = None;
+        self.free.push(key);
+    }
+
+    /// Append the taker identified by `key` to the queue unless it is already queued.
+    pub(crate) fn enqueue(&mut self, key: usize) {
+        if !self.takers.contains(&key) {
+            self.takers.push_back(key);
+        }
+    }
+
+    /// Check whether the taker identified by `key` is the first in the queue,
+    /// or whether the queue is empty if `key` is `None`.
+    pub(crate) fn is_next_taker(&self, key: Option<usize>) -> bool {
+        self.takers.front().copied() == key
+    }
+
+    /// Remove the taker identified by `key` from the queue, returns whether it was the first one.
+    pub(crate) fn dequeue(&mut self, key: usize) -> bool {
+        match self.takers.iter().position(|&k| k == key) {
+            Some(i) => {
+                self.takers.remove(i);
+                i == 0
+            }
+            None => false,
+        }
+    }
+
+    /// Number of modifications of the value so far.
+    pub(crate) fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Record a modification of the value from `prev` to `new` and take all registered wakers.
+    ///
+    /// Notifications may be recorded in another order than the modifications,
+    /// so the gate follows the `current` value loaded under the lock instead of `new`:
+    /// the last notification always leaves it consistent with the final value.
+    pub(crate) fn notify(&mut self, prev: T, new: T, current: T) -> Vec<Waker> {
+        self.version += 1;
+        for tracker in self.milestones.iter_mut().flatten() {
+            tracker.observe(new);
+        }
+        for edge in self.edges.iter_mut().flatten() {
+            edge.observe(prev, new);
+        }
+        if let Some(gate) = &mut self.gate {
+            gate.observe(current);
+        }
+        self.take_all()
+    }
+
+    /// Whether the counter is closed.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Record closing of the counter and take all registered wakers.
+    pub(crate) fn close(&mut self) -> Vec<Waker> {
+        self.closed = true;
+        self.take_all()
+    }
+
+    /// Take all registered wakers, leaving the slots allocated to their futures.
+    pub(crate) fn take_all(&mut self) -> Vec<Waker> {
+        self.slots.iter_mut().filter_map(Option::take).collect()
+    }
+
+    /// Start tracking `tracker`, returns the key identifying it.
+    pub(crate) fn add_milestones(&mut self, tracker: Tracker<T>) -> usize {
+        insert(&mut self.milestones, tracker)
+    }
+
+    /// Stop tracking the milestones identified by `key`.
+    pub(crate) fn remove_milestones(&mut self, key: usize) {
+        self.milestones[key] = None;
+    }
+
+    /// Take the next reached milestone of the tracker identified by `key`,
+    /// `Ok(None)` if all of them are delivered, `Err(())` if some are still ahead.
+    pub(crate) fn next_milestone(&mut self, key: usize) -> Result<Option<MilestoneReached<T>>, ()> {
+        self.milestones[key]
+            .as_mut()
+            .expect("Milestones are tracked until dropped")
+            .next_event()
+    }
+
+    /// Start watching `edge`, returns the key identifying it.
+    pub(crate) fn add_edge(&mut self, edge: Edge<T>) -> usize {
+        insert(&mut self.edges, edge)
+    }
+
+    /// Stop watching the edge identified by `key`.
+    pub(crate) fn remove_edge(&mut self, key: usize) {
+        self.edges[key] = None;
+    }
+
+    /// Value which crossed the edge identified by `key` if it is crossed.
+    pub(crate) fn edge_crossed(&self, key: usize) -> Option<T> {
+        self.edges[key]
+            .as_ref()
+            .expect("Edges are watched until dropped")
+            .crossed()
+    }
+
+    /// Replace the gate controlled by the watermarks.
+    pub(crate) fn set_gate(&mut self, gate: Gate<T>) {
+        self.gate = Some(gate);
+    }
+
+    /// State of the gate if the counter has watermarks.
+    pub(crate) fn gate_state(&self) -> Option<GateState> {
+        self.gate.as_ref().map(Gate::state)
+    }
+}
+
+/// Store `item` in the first vacant entry of `slab`, returns its index.
+fn insert<I>(slab: &mut Vec<Option<I>>, item: I) -> usize {
+    match slab.iter().position(Option::is_none) {
+        Some(key) => {
+            slab[key] = Some(item);
+            key
+        }
+        None => {
+            slab.push(Some(item));
+            slab.len() - 1
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::wait::Waiter;
+use crate::{Closed, Inner, Value};
+#[cfg(feature = "futures")]
+use futures_core::FusedFuture;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Watermarks of a [Counter](crate::Counter) limiting its producers with hysteresis.
+///
+/// The gate pauses once a modification brings the value to at least `high`
+/// and opens again only once a modification brings it below `low`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Watermarks<T> {
+    /// Value below which a paused gate opens.
+    pub low: T,
+    /// Value from which an open gate pauses.
+    pub high: T,
+}
+
+/// State of the gate of a [Counter](crate::Counter) with [Watermarks].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GateState {
+    /// Producers may proceed.
+    Open,
+    /// Producers should wait until the value drains below the low watermark.
+    Paused,
+}
+
+/// Gate of a counter, updated under the lock of the waiters.
+#[derive(Debug)]
+pub(crate) struct Gate<T> {
+    watermarks: Watermarks<T>,
+    state: GateState,
+}
+
+impl<T: Value> Gate<T> {
+    pub(crate) fn new(watermarks: Watermarks<T>, value: T) -> Self {
+        assert!(
+            watermarks.low <= watermarks.high,
+            "Low watermark must not exceed the high one"
+        );
+        let mut gate = Self {
+            watermarks,
+            state: GateState::Open,
+        };
+        gate.observe(value);
+        gate
+    }
+
+    /// Switch the state if `value` passes the watermark of the current state.
+    pub(crate) fn observe(&mut self, value: T) {
+        self.state = match self.state {
+            GateState::Open if value >= self.watermarks.high => GateState::Paused,
+            GateState::Paused if value < self.watermarks.low => GateState::Open,
+            state => state,
+        };
+    }
+
+    pub(crate) fn state(&self) -> GateState {
+        self.state
+    }
+}
+
+/// Future resolving once the gate of a borrowed [Counter](crate::Counter) is in a given state.
+///
+/// Created by [Counter::wait_gate](crate::Counter::wait_gate)
+/// and [Counter::wait_writable](crate::Counter::wait_writable).
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitGate<'a, T: Value = usize> {
+    waiter: Waiter<T, &'a Inner<T>>,
+    state: GateState,
+}
+
+impl<'a, T: Value> WaitGate<'a, T> {
+    pub(crate) fn new(inner: &'a Inner<T>, state: GateState) -> Self {
+        Self {
+            waiter: Waiter::new(inner),
+            state,
+        }
+    }
+}
+
+impl<T: Value> Future for WaitGate<'_, T> {
+    type Output = Result<T, Closed<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.waiter.poll_gate(cx, this.state)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Value> FusedFuture for WaitGate<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.waiter.is_done()
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::{Counter, GateState, Watermarks};
+    use std::ops::Mul;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn gate_pauses_with_hysteresis() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let in_flight = Counter::to(0).with_watermarks(Watermarks { low: 2, high: 4 });
+        assert_eq!(in_flight.gate_state(), Some(GateState::Open));
+
+        let guards = (0..4).map(|_| in_flight.guard()).collect::<Vec<_>>();
+        assert_eq!(in_flight.gate_state(), Some(GateState::Paused));
+
+        let paused = time::timeout(counting_interval, in_flight.wait_gate(GateState::Paused)).await;
+        assert!(matches!(paused, Ok(Ok(4))));
+
+        // Draining to the low watermark is not enough to resume.
+        tokio::spawn(async move {
+            for guard in guards {
+                time::sleep(counting_interval).await;
+                drop(guard);
+            }
+        });
+
+        let r = time::timeout(counting_interval.mul(20), in_flight.wait_writable()).await;
+        assert!(matches!(r, Ok(Ok(1))));
+        assert_eq!(in_flight.gate_state(), Some(GateState::Open));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{Counter, Inner, Value};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Weak};
+
+/// Handle of a [Counter] which neither keeps it alive nor keeps it open.
+///
+/// Analogous to [std::sync::Weak], it is upgraded to a [Counter] as long as
+/// any writing handle still exists. Created by [Counter::downgrade].
+#[derive(Debug, Clone)]
+pub struct WeakCounter<T: Value = usize> {
+    inner: Weak<Inner<T>>,
+}
+
+impl<T: Value> Counter<T> {
+    /// Create a [WeakCounter] referencing this [Counter].
+    pub fn downgrade(&self) -> WeakCounter<T> {
+        WeakCounter {
+            inner: Arc::downgrade(&self.inner),
+        }
+    }
+}
+
+impl<T: Value> WeakCounter<T> {
+    /// Upgrade to a [Counter] if the counter still has a writing handle.
+    ///
+    /// Once the last writing handle is dropped, the counter is closed
+    /// and cannot be revived, thus `None` is returned even if readers keep it alive.
+    pub fn upgrade(&self) -> Option<Counter<T>> {
+        let inner = self.inner.upgrade()?;
+        inner
+            .writers
+            .fetch_update(Ordering::Acquire, Ordering::Acquire, |writers| {
+                (writers > 0).then_some(writers + 1)
+            })
+            .ok()?;
+        Some(Counter { inner })
+    }
+
+    /// Fetch the number of handles able to modify the [Counter] value.
+    pub fn writer_count(&self) -> usize {
+        self.inner
+            .upgrade()
+            .map_or(0, |inner| inner.writers.load(Ordering::Acquire))
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use crate::{Closed, Counter};
+    use std::ops::Mul;
+    use std::time::Duration;
+    use tokio::time;
+
+    #[tokio::test]
+    async fn weak_counter_does_not_keep_counter_open() {
+        let _ = pretty_env_logger::try_init();
+
+        let counting_interval = Duration::from_millis(10);
+
+        let counter = Counter::to(10);
+        let weak = counter.downgrade();
+        let wait = counter.wait_for_owned(10);
+
+        // Monitoring code upgrades temporarily.
+        let mut count = weak.upgrade().expect("Counter has a writer");
+        assert_eq!(weak.writer_count(), 2);
+        count += 3;
+        drop(count);
+
+        drop(counter);
+        assert!(weak.upgrade().is_none());
+        assert_eq!(weak.writer_count(), 0);
+
+        let r = time::timeout(counting_interval.mul(20), wait).await;
+        assert!(matches!(r, Ok(Err(Closed { value: 3 }))));
+    }
+}
@@ -0,0 +1,171 @@
+//! Model checking of the wake protocol, run with
+//! `RUSTFLAGS="--cfg loom" cargo test --release --test loom`.
+#![cfg(loom)]
+
+use async_counter::{Closed, Counter, GateState, MemoryOrdering, Watermarks};
+use loom::future::block_on;
+use loom::sync::atomic::{AtomicUsize, Ordering};
+use loom::sync::Arc;
+use loom::thread;
+
+/// Models with three threads explode without bounding the preemptions.
+fn bounded_model(f: impl Fn() + Sync + Send + 'static) {
+    let mut model = loom::model::Builder::new();
+    model.preemption_bound = Some(2);
+    model.check(f);
+}
+
+#[test]
+fn increment_wakes_pending_wait() {
+    loom::model(|| {
+        let counter = Counter::to(1);
+        let mut count = counter.clone();
+
+        let writer = thread::spawn(move || count += 1);
+
+        assert_eq!(block_on(counter.wait_for(1)), Ok(1));
+        writer.join().expect("Writer thread must not panic");
+    });
+}
+
+#[test]
+fn decrement_wakes_pending_wait() {
+    loom::model(|| {
+        let counter = Counter::new(2, 2);
+        let mut count = counter.clone();
+
+        let writer = thread::spawn(move || count -= 2);
+
+        assert_eq!(block_on(counter.wait_zero()), Ok(0));
+        writer.join().expect("Writer thread must not panic");
+    });
+}
+
+#[test]
+fn set_wakes_pending_wait() {
+    loom::model(|| {
+        let counter = Counter::to(3);
+        let count = counter.clone();
+
+        let writer = thread::spawn(move || count.set(3));
+
+        assert_eq!(block_on(counter.wait_until(|v| v == 3)), Ok(3));
+        writer.join().expect("Writer thread must not panic");
+    });
+}
+
+#[test]
+fn set_target_wakes_pending_wait() {
+    loom::model(|| {
+        let counter = Counter::new(2, 3);
+        let count = counter.clone();
+
+        let writer = thread::spawn(move || count.set_target(2));
+
+        assert_eq!(block_on(counter.wait()), Ok(2));
+        writer.join().expect("Writer thread must not panic");
+    });
+}
+
+#[test]
+fn dropping_last_handle_fails_pending_wait() {
+    loom::model(|| {
+        let counter = Counter::to(1);
+        let wait = counter.wait_for_owned(1);
+
+        let writer = thread::spawn(move || drop(counter));
+
+        assert_eq!(block_on(wait), Err(Closed { value: 0 }));
+        writer.join().expect("Writer thread must not panic");
+    });
+}
+
+#[test]
+fn increment_wakes_pending_take() {
+    loom::model(|| {
+        let counter = Counter::to(1);
+        let mut count = counter.clone();
+
+        let writer = thread::spawn(move || count += 1);
+
+        assert_eq!(block_on(counter.take(1)), Ok(0));
+        writer.join().expect("Writer thread must not panic");
+    });
+}
+
+#[test]
+fn concurrent_increments_wake_pending_wait() {
+    bounded_model(|| {
+        let counter = Counter::to(2);
+        let mut first = counter.clone();
+        let mut second = counter.clone();
+
+        let writers = [
+            thread::spawn(move || first += 1),
+            thread::spawn(move || second += 1),
+        ];
+
+        assert_eq!(block_on(counter.wait_for(2)), Ok(2));
+        for writer in writers {
+            writer.join().expect("Writer thread must not panic");
+        }
+    });
+}
+
+#[test]
+fn increment_wakes_all_pending_waits() {
+    bounded_model(|| {
+        let counter = Counter::to(1);
+        let mut count = counter.clone();
+        let waiting = counter.clone();
+
+        let writer = thread::spawn(move || count += 1);
+        let waiter = thread::spawn(move || block_on(waiting.wait_for(1)));
+
+        assert_eq!(block_on(counter.wait_for(1)), Ok(1));
+        assert_eq!(waiter.join().expect("Waiter thread must not panic"), Ok(1));
+        writer.join().expect("Writer thread must not panic");
+    });
+}
+
+#[test]
+fn concurrent_inc_dec_opens_pending_gate() {
+    bounded_model(|| {
+        let counter = Counter::new(4, 4).with_watermarks(Watermarks { low: 2, high: 4 });
+        let mut first = counter.clone();
+        let mut second = counter.clone();
+
+        // Either order drains the counter to 1 below the low watermark,
+        // the gate may open earlier at 0 but must end open.
+        let writers = [
+            thread::spawn(move || first += 1),
+            thread::spawn(move || second -= 4),
+        ];
+
+        assert!(block_on(counter.wait_writable()).is_ok());
+        for writer in writers {
+            writer.join().expect("Writer thread must not panic");
+        }
+        assert_eq!(counter.value(), 1);
+        assert_eq!(counter.gate_state(), Some(GateState::Open));
+    });
+}
+
+#[test]
+fn wait_synchronizes_with_increment() {
+    loom::model(|| {
+        let counter = Counter::to(1).with_ordering(MemoryOrdering::AcquireRelease);
+        let mut count = counter.clone();
+        let data = Arc::new(AtomicUsize::new(0));
+        let written = data.clone();
+
+        let writer = thread::spawn(move || {
+            written.store(42, Ordering::Relaxed);
+            count += 1;
+        });
+
+        assert_eq!(block_on(counter.wait_for(1)), Ok(1));
+        assert_eq!(data.load(Ordering::Relaxed), 42);
+        writer.join().expect("Writer thread must not panic");
+    });
+}